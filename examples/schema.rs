@@ -1,21 +1,32 @@
 use std::env::current_dir;
-use std::fs::create_dir_all;
 
-use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+use cosmwasm_schema::{export_schema, schema_for, write_api};
 
-use cw_starter::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use cw_starter::state::{Ballot, Config, Poll};
+use cw_starter::msg::{
+    CreatePollResponse, Cw3QueryMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, ReceiveMsg,
+    SudoMsg,
+};
+use cw_starter::state::{Ballot, Config, Poll, Stats};
 
 fn main() {
+    // Entry point messages and query responses, in schema/cw-starter.json for ts-codegen.
+    // Recreates the schema directory
+    write_api! {
+        instantiate: InstantiateMsg,
+        execute: ExecuteMsg,
+        query: QueryMsg,
+        migrate: MigrateMsg,
+        sudo: SudoMsg,
+    }
+
+    // Types outside of the entry points
     let mut out_dir = current_dir().unwrap();
     out_dir.push("schema");
-    create_dir_all(&out_dir).unwrap();
-    remove_schemas(&out_dir).unwrap();
-
-    export_schema(&schema_for!(InstantiateMsg), &out_dir);
-    export_schema(&schema_for!(ExecuteMsg), &out_dir);
-    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(ReceiveMsg), &out_dir);
+    export_schema(&schema_for!(Cw3QueryMsg), &out_dir);
+    export_schema(&schema_for!(CreatePollResponse), &out_dir);
     export_schema(&schema_for!(Config), &out_dir);
     export_schema(&schema_for!(Poll), &out_dir);
     export_schema(&schema_for!(Ballot), &out_dir);
+    export_schema(&schema_for!(Stats), &out_dir);
 }
@@ -1,18 +1,75 @@
+use bech32::{ToBase32, Variant};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult,
+    from_binary, to_binary, to_vec, Addr, Api, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps,
+    DepsMut, Empty, Env, MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, SubMsg,
+    SubMsgResult, Timestamp, Uint128, WasmMsg,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::{Bound, PrimaryKey};
+use cw_utils::{must_pay, nonpayable, Expiration, PaymentError};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 
 use crate::error::ContractError;
+use crate::events::{
+    evt_ballot_cast, evt_ballot_changed, evt_poll_closed, evt_poll_created, evt_vote,
+    expiration_attribute,
+};
 use crate::msg::{
-    AllPollsResponse, ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg, VoteResponse,
+    AllPollsResponse, AuditLogResponse, ConfigResponse, ConfigUpdate, ContractInfoResponse,
+    CreatePollResponse, CroncatAction, CroncatBoundary, CroncatExecuteMsg, CroncatInterval,
+    CroncatTaskRequest, Cw3ProposalListResponse, Cw3ProposalResponse, Cw3QueryMsg, Cw3Status,
+    Cw3ThresholdResponse, Cw3Vote, Cw3VoteInfo, Cw3VoteListResponse, Cw3VoteResponse,
+    Cw721ExecuteMsg, DaoHookExecuteMsg, DaoQueryMsg, ExecuteMsg, HooksResponse, InfoResponse,
+    InstantiateMsg, MigrateMsg, NoisCallback, NoisProxyExecuteMsg, OptionResult,
+    OptionTallyResponse, OrderBy, ParticipationResponse, PendingFeesResponse, PollExistsResponse,
+    PollHookExecuteMsg, PollHookMsg, PollMetadata, PollMetadataResponse, PollResponse,
+    PollResultsResponse, PollVotersResponse, ProposalHookMsg, QueryMsg, ReceiveMsg,
+    RemovalResponse, ResultsPermit, ResultsPermitPayload, SignedVotePayload, SudoMsg,
+    TallyAtHeightResponse, TopVotersResponse, VoteHookMsg, VoteResponse, VotersByOptionResponse,
+    VotesByAddressResponse, VotingPowerAtHeightResponse, WinnerResponse,
+};
+use crate::state::{
+    AuditEntry, Ballot, Config, Hooks, Lottery, Poll, PollActions, PollId, PollOption, PollStatus,
+    RandomnessJob, Removal, RewardPool, Role, Stats, StoredOption, TieBreak, AUDIT_LOG, BALLOTS,
+    BANNED, CONFIG, CREATION_DEPOSITS, CW20_FEES, DEFAULT_MAX_OPTION_LENGTH,
+    DEFAULT_MAX_QUESTION_LENGTH, DEPOSITS, FEES, FINALIZE_TASKS, FIRST_CONFIG, FLAGS, FLAG_COUNTS,
+    HOOKS, LAST_ACTION, LOTTERIES, NEXT_JOB_ID, NEXT_POLL_ID, OPEN_POLLS, OPTION_VOTERS,
+    PARTICIPATION, PARTICIPATION_RANKING, PENDING_FINALIZE_TASK, POLLS, POLL_ACTIONS, POLL_COUNT,
+    POLL_ENTRIES, PROPOSAL_HOOKS, RANDOMNESS_JOBS, REMOVALS, REWARD_CLAIMS, REWARD_POOLS,
+    REWARD_SPONSORS, ROLES, SLUGS, STATS, STORED_BALLOTS, STORED_OPTIONS, TALLIES, VOTERS,
+    VOTE_ESCROW, VOTE_HOOKS, VOTE_NONCES, VOTE_PROXIES,
 };
-use crate::state::{Ballot, Config, Poll, BALLOTS, CONFIG, POLLS};
+use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20ReceiveMsg};
 
 const CONTRACT_NAME: &str = "crates.io:cw-starter";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+// Mints of the "I Voted" NFT, replied only on failure
+const VOTED_NFT_REPLY_ID: u64 = 1;
+#[cfg(feature = "tokenfactory")]
+const PARTICIPATION_REPLY_ID: u64 = 2;
+// Creation of a CronCat task, replied on success for the task hash
+const FINALIZE_TASK_REPLY_ID: u64 = 3;
+// Hook notifications, replied only on failure
+const HOOK_REPLY_ID: u64 = 4;
+// Actions of a passed proposal poll, replied only on failure
+const ACTIONS_REPLY_ID: u64 = 5;
+// Removal of a CronCat task, replied only on failure
+const CANCEL_TASK_REPLY_ID: u64 = 6;
+// Gas of the FinalizeExpired run by CronCat, counting the ballots of lazy polls
+const FINALIZE_GAS_LIMIT: u64 = 1_000_000;
+
+// Tags a poll can have
+const MAX_TAGS: usize = 5;
+// Option ids are a u8
+const MAX_OPTIONS: u32 = u8::MAX as u32 + 1;
+
+// Page size of list queries
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -25,14 +82,231 @@ pub fn instantiate(
     let admin = msg.admin.unwrap_or_else(|| info.sender.to_string());
     let validated_admin = deps.api.addr_validate(&admin)?;
     let config = Config {
-        admin: validated_admin.clone(),
+        vote_cooldown: msg.vote_cooldown,
+        min_options: msg.min_options.unwrap_or(2),
+        max_options: msg.max_options.unwrap_or(5),
+        allow_public_creation: true,
+        paused: false,
+        creation_fee: msg.creation_fee,
+        cw20_creation_fee: msg
+            .cw20_creation_fee
+            .map(|fee| fee_to_verified(deps.api, fee))
+            .transpose()?,
+        creation_deposit: msg.creation_deposit,
+        deposit_slash_ratio: msg.deposit_slash_ratio.unwrap_or_else(Decimal::one),
+        flag_threshold: msg.flag_threshold,
+        max_open_polls_per_creator: msg.max_open_polls_per_creator,
+        max_question_length: msg
+            .max_question_length
+            .unwrap_or(DEFAULT_MAX_QUESTION_LENGTH),
+        max_option_length: msg.max_option_length.unwrap_or(DEFAULT_MAX_OPTION_LENGTH),
+        debug_events: false,
+        voted_nft: None,
+        participation_denom: None,
+        nois_proxy: None,
+        croncat_tasks: None,
+        dao: None,
     };
+    validate_option_bounds(&config)?;
+    validate_creation_funds(&config)?;
     CONFIG.save(deps.storage, &config)?;
+    STATS.save(deps.storage, &Stats::default())?;
+    POLL_COUNT.save(deps.storage, &0)?;
+    NEXT_POLL_ID.save(deps.storage, &1)?;
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(validated_admin.as_str()))?;
     Ok(Response::new()
         .add_attribute("action", "instantiate")
         .add_attribute("admin", validated_admin.to_string()))
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    ensure_migration_from(deps.storage)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    // Rewrites the polls stored with an older layout in the current one, polls
+    // already in it are left alone
+    let legacy = STORED_OPTIONS
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|entry| {
+            entry.as_ref().map_or(true, |(_, stored)| {
+                stored
+                    .options
+                    .iter()
+                    .any(|option| !matches!(option, StoredOption::Option(_)))
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    for (poll_id, stored) in &legacy {
+        for option in stored.options.clone() {
+            if let StoredOption::Tally(label, votes) = option {
+                TALLIES.save(
+                    deps.storage,
+                    (poll_id.clone(), label),
+                    &Uint128::from(votes),
+                    env.block.height,
+                )?;
+            }
+        }
+        let poll = POLLS.load(deps.storage, poll_id)?;
+        POLLS.save(deps.storage, poll_id, &poll)?;
+    }
+    // The first layout only stored the admin, the polls and the ballots
+    if let Ok(first_config) = FIRST_CONFIG.load(deps.storage) {
+        migrate_first_layout(deps.storage, deps.api, &env, first_config.admin)?;
+    }
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("polls", legacy.len().to_string()))
+}
+
+// Only this contract migrates into this one, and never to an older version
+fn ensure_migration_from(storage: &dyn Storage) -> Result<(), ContractError> {
+    let stored = get_contract_version(storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::WrongContract {
+            contract: stored.contract,
+        });
+    }
+    let parse = |version: &str| {
+        version
+            .parse::<semver::Version>()
+            .map_err(|err| StdError::generic_err(err.to_string()))
+    };
+    if parse(&stored.version)? > parse(CONTRACT_VERSION)? {
+        return Err(ContractError::MigrationDowngrade {
+            stored: stored.version,
+            current: CONTRACT_VERSION.to_string(),
+        });
+    }
+    Ok(())
+}
+
+// Everything added since the first layout, rebuilt from its polls and ballots.
+// Its ballots all weigh 1 and were counted once
+fn migrate_first_layout(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    admin: Addr,
+) -> Result<(), ContractError> {
+    let config = Config {
+        vote_cooldown: None,
+        min_options: 2,
+        max_options: 5,
+        allow_public_creation: true,
+        paused: false,
+        creation_fee: None,
+        cw20_creation_fee: None,
+        creation_deposit: None,
+        deposit_slash_ratio: Decimal::one(),
+        flag_threshold: None,
+        max_open_polls_per_creator: None,
+        max_question_length: DEFAULT_MAX_QUESTION_LENGTH,
+        max_option_length: DEFAULT_MAX_OPTION_LENGTH,
+        debug_events: false,
+        voted_nft: None,
+        participation_denom: None,
+        nois_proxy: None,
+        croncat_tasks: None,
+        dao: None,
+    };
+    CONFIG.save(storage, &config)?;
+    cw_ownable::initialize_owner(storage, api, Some(admin.as_str()))?;
+    NEXT_POLL_ID.save(storage, &1)?;
+
+    let ballots = STORED_BALLOTS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut voters: BTreeMap<String, u64> = BTreeMap::new();
+    let mut participation: BTreeMap<Addr, u64> = BTreeMap::new();
+    for ((voter, poll_id), ballot) in &ballots {
+        let weighted = Ballot {
+            option: ballot.option.clone(),
+            weight: Uint128::one(),
+        };
+        BALLOTS.save(storage, (voter, poll_id), &weighted, env.block.height)?;
+        VOTERS.save(storage, (poll_id.clone(), voter.clone()), &Empty {})?;
+        OPTION_VOTERS.save(
+            storage,
+            (poll_id.clone(), ballot.option.clone(), voter.clone()),
+            &Empty {},
+        )?;
+        *voters.entry(poll_id.clone()).or_default() += 1;
+        *participation.entry(voter.clone()).or_default() += 1;
+    }
+    for (voter, polls) in &participation {
+        PARTICIPATION.save(storage, voter.clone(), polls)?;
+        PARTICIPATION_RANKING.save(storage, (*polls, voter.clone()), &Empty {})?;
+    }
+
+    // Polls couldn't be closed, they are all still open
+    let polls = POLL_ENTRIES
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut open_polls: BTreeMap<Addr, u32> = BTreeMap::new();
+    for (poll_id, mut poll) in polls.iter().cloned() {
+        poll.voter_count = voters.get(&poll_id).copied().unwrap_or_default();
+        poll.total_votes = poll.voter_count;
+        POLL_ENTRIES.save(storage, &poll_id, &poll)?;
+        *open_polls.entry(poll.creator).or_default() += 1;
+    }
+    for (creator, count) in open_polls {
+        OPEN_POLLS.save(storage, creator, &count)?;
+    }
+    POLL_COUNT.save(storage, &(polls.len() as u64))?;
+    STATS.save(
+        storage,
+        &Stats {
+            total_polls: polls.len() as u64,
+            total_ballots: ballots.len() as u64,
+            unique_voters: participation.len() as u64,
+        },
+    )?;
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match (msg.id, msg.result) {
+        // CronCat answers with the hash of the new task
+        (FINALIZE_TASK_REPLY_ID, SubMsgResult::Ok(res)) => {
+            let poll_id = PENDING_FINALIZE_TASK.load(deps.storage)?;
+            PENDING_FINALIZE_TASK.remove(deps.storage);
+            let task_hash = res
+                .data
+                .map(|data| String::from_utf8(data.0))
+                .transpose()
+                .map_err(|_| StdError::generic_err("Invalid CronCat task hash"))?
+                .ok_or_else(|| StdError::generic_err("Missing CronCat task hash"))?;
+            FINALIZE_TASKS.save(deps.storage, poll_id.clone(), &task_hash)?;
+            Ok(Response::new()
+                .add_attribute("action", "schedule_finalize")
+                .add_attribute("poll_id", poll_id)
+                .add_attribute("task_hash", task_hash))
+        }
+        // The vote stands, the voter just doesn't get the NFT
+        (VOTED_NFT_REPLY_ID, SubMsgResult::Err(err)) => Ok(Response::new()
+            .add_attribute("action", "voted_nft_failed")
+            .add_attribute("error", err)),
+        #[cfg(feature = "tokenfactory")]
+        (PARTICIPATION_REPLY_ID, SubMsgResult::Err(err)) => Ok(Response::new()
+            .add_attribute("action", "participation_mint_failed")
+            .add_attribute("error", err)),
+        // A failing subscriber doesn't block the polls
+        (HOOK_REPLY_ID, SubMsgResult::Err(err)) => Ok(Response::new()
+            .add_attribute("action", "hook_failed")
+            .add_attribute("error", err)),
+        (ACTIONS_REPLY_ID, SubMsgResult::Err(err)) => Ok(Response::new()
+            .add_attribute("action", "poll_action_failed")
+            .add_attribute("error", err)),
+        // The poll is final anyway, a task CronCat already dropped just fails
+        (CANCEL_TASK_REPLY_ID, SubMsgResult::Err(err)) => Ok(Response::new()
+            .add_attribute("action", "cancel_finalize_failed")
+            .add_attribute("error", err)),
+        (id, _) => Err(StdError::generic_err(format!("Unknown reply id {id}")).into()),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -40,384 +314,10416 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    match msg {
+    // Poll creation and voting are stopped while paused
+    if matches!(
+        msg,
+        ExecuteMsg::CreatePoll { .. }
+            | ExecuteMsg::Receive(_)
+            | ExecuteMsg::Vote { .. }
+            | ExecuteMsg::VoteMany { .. }
+            | ExecuteMsg::VoteBySignature { .. }
+            | ExecuteMsg::FundReward { .. }
+    ) && CONFIG.load(deps.storage)?.paused
+    {
+        return Err(ContractError::ContractPaused {});
+    }
+
+    let response = match msg {
         ExecuteMsg::CreatePoll {
+            slug,
+            question,
+            options,
+            veto,
+            vote_deposit,
+            expires_at,
+            tags,
+            hide_results,
+            lazy_tally,
+            vote_token,
+            tie_break,
+            actions,
+            finalize_fee,
+        } => execute_create_poll(
+            deps,
+            env,
+            info,
+            slug,
+            question,
+            options,
+            veto,
+            vote_deposit,
+            expires_at,
+            tags,
+            hide_results,
+            lazy_tally,
+            vote_token,
+            tie_break,
+            actions,
+            finalize_fee,
+        ),
+        ExecuteMsg::Vote {
+            poll_id,
+            vote,
+            on_behalf_of,
+        } => {
+            let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+            execute_vote(deps, env, info, poll_id, vote, on_behalf_of)
+        }
+        ExecuteMsg::SetVoteProxy { proxy } => execute_set_vote_proxy(deps, env, info, proxy),
+        ExecuteMsg::VoteMany { votes } => {
+            let votes = votes
+                .into_iter()
+                .map(|(poll_id, vote)| Ok((resolve_poll_id(deps.storage, poll_id)?, vote)))
+                .collect::<StdResult<_>>()?;
+            execute_vote_many(deps, env, info, votes)
+        }
+        ExecuteMsg::ClosePoll { poll_id } => {
+            let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+            execute_close_poll(deps, env, info, poll_id)
+        }
+        ExecuteMsg::FinalizeExpired { poll_id } => {
+            let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+            execute_finalize_expired(deps, env, info, poll_id)
+        }
+        ExecuteMsg::ScheduleFinalize { poll_id } => {
+            let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+            execute_schedule_finalize(deps, env, info, poll_id)
+        }
+        ExecuteMsg::VetoPoll { poll_id } => {
+            let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+            execute_veto_poll(deps, env, info, poll_id)
+        }
+        ExecuteMsg::ClaimDeposit { poll_id } => {
+            let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+            execute_claim_deposit(deps, env, info, poll_id)
+        }
+        ExecuteMsg::FundReward { poll_id } => {
+            let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+            let pool = RewardPool {
+                native: info.funds.clone(),
+                cw20: vec![],
+            };
+            if pool.is_empty() {
+                return Err(PaymentError::NoFunds {}.into());
+            }
+            fund_reward(deps.storage, info.sender, poll_id, pool)
+        }
+        ExecuteMsg::ClaimReward { poll_ids } => {
+            let poll_ids = resolve_poll_ids(deps.storage, poll_ids)?;
+            execute_claim_reward(deps, env, info, poll_ids)
+        }
+        ExecuteMsg::StartLottery {
+            poll_id,
+            winners,
+            prize,
+        } => {
+            let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+            execute_start_lottery(deps, env, info, poll_id, winners, prize)
+        }
+        ExecuteMsg::NoisReceive { callback } => execute_nois_receive(deps, env, info, callback),
+        ExecuteMsg::AddHook { addr } => execute_add_hook(deps, env, info, addr),
+        ExecuteMsg::RemoveHook { addr } => execute_remove_hook(deps, env, info, addr),
+        ExecuteMsg::AddProposalHook { address } => {
+            execute_dao_hook(deps, info, &PROPOSAL_HOOKS, address, true)
+        }
+        ExecuteMsg::RemoveProposalHook { address } => {
+            execute_dao_hook(deps, info, &PROPOSAL_HOOKS, address, false)
+        }
+        ExecuteMsg::AddVoteHook { address } => {
+            execute_dao_hook(deps, info, &VOTE_HOOKS, address, true)
+        }
+        ExecuteMsg::RemoveVoteHook { address } => {
+            execute_dao_hook(deps, info, &VOTE_HOOKS, address, false)
+        }
+        ExecuteMsg::GrantRole { address, role } => {
+            execute_grant_role(deps, env, info, address, role)
+        }
+        ExecuteMsg::RevokeRole { address } => execute_revoke_role(deps, env, info, address),
+        ExecuteMsg::UpdateOwnership(action) => execute_update_ownership(deps, env, info, action),
+        ExecuteMsg::UpdateConfig(update) => execute_update_config(deps, env, info, update),
+        ExecuteMsg::SetPaused { paused } => execute_set_paused(deps, env, info, paused),
+        ExecuteMsg::BanAddress { address } => execute_ban_address(deps, env, info, address, true),
+        ExecuteMsg::UnbanAddress { address } => {
+            execute_ban_address(deps, env, info, address, false)
+        }
+        ExecuteMsg::FlagPoll { poll_id, reason } => {
+            let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+            execute_flag_poll(deps, env, info, poll_id, reason)
+        }
+        ExecuteMsg::ResumePoll { poll_id } => {
+            let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+            execute_resume_poll(deps, env, info, poll_id)
+        }
+        ExecuteMsg::EmergencyRemove { poll_ids } => {
+            let poll_ids = resolve_poll_ids(deps.storage, poll_ids)?;
+            execute_emergency_remove(deps, env, info, poll_ids)
+        }
+        ExecuteMsg::PruneBallots { poll_id, limit } => {
+            let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+            execute_prune_ballots(deps, env, info, poll_id, limit)
+        }
+        ExecuteMsg::RemovePoll { poll_id, reason } => {
+            let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+            execute_remove_poll(deps, env, info, poll_id, reason)
+        }
+        ExecuteMsg::WithdrawFees { to } => execute_withdraw_fees(deps, env, info, to),
+        ExecuteMsg::Receive(cw20_msg) => execute_receive(deps, env, info, cw20_msg),
+        ExecuteMsg::ReclaimDeposit { poll_id } => {
+            let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+            execute_reclaim_deposit(deps, env, info, poll_id)
+        }
+        ExecuteMsg::VoteBySignature {
             poll_id,
+            option,
+            voter_pubkey,
+            signature,
+            nonce,
+        } => execute_vote_by_signature(deps, env, poll_id, option, voter_pubkey, signature, nonce),
+    }?;
+    // Lets indexers of several deployments tell the releases apart
+    Ok(response.add_attribute("contract_version", CONTRACT_VERSION))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_create_poll(
+    mut deps: DepsMut,
+    env: Env,
+    mut info: MessageInfo,
+    slug: Option<PollId>,
+    question: String,
+    options: Vec<String>,
+    veto: Option<String>,
+    vote_deposit: Option<Coin>,
+    expires_at: Option<Timestamp>,
+    tags: Option<Vec<String>>,
+    hide_results: Option<bool>,
+    lazy_tally: Option<bool>,
+    vote_token: Option<String>,
+    tie_break: Option<TieBreak>,
+    actions: Option<PollActions>,
+    finalize_fee: Option<Coin>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    // The rest of the funds pays the creation
+    if let Some(fee) = &finalize_fee {
+        info.funds = split_funds(&info.funds, fee)?;
+    }
+    let deposit = match (config.creation_fee, config.cw20_creation_fee) {
+        // The fee can only be paid by sending the token
+        (None, Some(fee)) => {
+            return Err(ContractError::InsufficientCw20Fee {
+                token: fee.address.to_string(),
+                expected: fee.amount,
+            })
+        }
+        (fee, _) => collect_creation_funds(deps.storage, &info, fee, config.creation_deposit)?,
+    };
+    let slug = slug.map(String::from);
+    let poll_id = assign_poll_id(deps.storage, slug.as_deref())?;
+    if let Some(deposit) = deposit {
+        CREATION_DEPOSITS.save(deps.storage, poll_id.clone(), &deposit)?;
+    }
+
+    let res = create_poll(
+        deps.branch(),
+        env.clone(),
+        info.sender,
+        poll_id.clone(),
+        slug,
+        question,
+        options,
+        veto,
+        vote_deposit,
+        expires_at,
+        tags,
+        hide_results,
+        lazy_tally,
+        vote_token,
+        tie_break,
+        actions,
+    )?;
+    match finalize_fee {
+        Some(fee) => {
+            Ok(res.add_submessage(finalize_task_msg(deps.storage, &env, &poll_id, vec![fee])?))
+        }
+        None => Ok(res),
+    }
+}
+
+// Funds left once part is taken out of them
+fn split_funds(funds: &[Coin], part: &Coin) -> Result<Vec<Coin>, ContractError> {
+    let paid = funds
+        .iter()
+        .find(|coin| coin.denom == part.denom)
+        .map(|coin| coin.amount)
+        .unwrap_or_default();
+    if paid < part.amount {
+        return Err(ContractError::InsufficientFee {
+            expected: part.clone(),
+        });
+    }
+    Ok(funds
+        .iter()
+        .map(|coin| match coin.denom == part.denom {
+            true => Coin::new((coin.amount - part.amount).u128(), &coin.denom),
+            false => coin.clone(),
+        })
+        .filter(|coin| !coin.amount.is_zero())
+        .collect())
+}
+
+// Checks the native funds cover the creation fee plus the creation deposit, both
+// in the same denom, keeps the fee and returns the deposit to hold
+fn collect_creation_funds(
+    storage: &mut dyn Storage,
+    info: &MessageInfo,
+    fee: Option<Coin>,
+    deposit: Option<Coin>,
+) -> Result<Option<Coin>, ContractError> {
+    let denom = match (&fee, &deposit) {
+        (Some(fee), _) => fee.denom.clone(),
+        (None, Some(deposit)) => deposit.denom.clone(),
+        (None, None) => {
+            nonpayable(info).map_err(|_| ContractError::UnexpectedFunds {})?;
+            return Ok(None);
+        }
+    };
+    let deposit_amount = deposit
+        .as_ref()
+        .map(|deposit| deposit.amount)
+        .unwrap_or_default();
+    let required = fee.as_ref().map(|fee| fee.amount).unwrap_or_default() + deposit_amount;
+
+    let paid = must_pay(info, &denom)?;
+    if paid < required {
+        return Err(ContractError::InsufficientFee {
+            expected: Coin::new(required.u128(), denom),
+        });
+    }
+    // Anything paid above the deposit is kept as fee
+    if fee.is_some() {
+        FEES.update(storage, denom, |fees| -> StdResult<_> {
+            Ok(fees.unwrap_or_default() + paid - deposit_amount)
+        })?;
+    }
+    Ok(deposit)
+}
+
+// Handles cw20 tokens sent to the contract with one of the ReceiveMsg
+fn execute_receive(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&cw20_msg.msg)? {
+        ReceiveMsg::CreatePoll {
+            slug,
             question,
             options,
-        } => execute_create_poll(deps, env, info, poll_id, question, options),
-        ExecuteMsg::Vote { poll_id, vote } => execute_vote(deps, env, info, poll_id, vote),
+            veto,
+            vote_deposit,
+            expires_at,
+            tags,
+            hide_results,
+            lazy_tally,
+            vote_token,
+            tie_break,
+            actions,
+        } => {
+            // The sender is the token contract
+            let config = CONFIG.load(deps.storage)?;
+            // Deposits are native coins, they can't be sent along with tokens
+            if let Some(deposit) = config.creation_deposit {
+                return Err(ContractError::InsufficientFee { expected: deposit });
+            }
+            let fee = config
+                .cw20_creation_fee
+                .filter(|fee| fee.address == info.sender)
+                .ok_or(ContractError::UnsupportedToken {
+                    token: info.sender.to_string(),
+                })?;
+            if cw20_msg.amount < fee.amount {
+                return Err(ContractError::InsufficientCw20Fee {
+                    token: fee.address.to_string(),
+                    expected: fee.amount,
+                });
+            }
+            CW20_FEES.update(deps.storage, fee.address, |fees| -> StdResult<_> {
+                Ok(fees.unwrap_or_default() + cw20_msg.amount)
+            })?;
+
+            let creator = deps.api.addr_validate(&cw20_msg.sender)?;
+            let slug = slug.map(String::from);
+            let poll_id = assign_poll_id(deps.storage, slug.as_deref())?;
+            create_poll(
+                deps,
+                env,
+                creator,
+                poll_id,
+                slug,
+                question,
+                options,
+                veto,
+                vote_deposit,
+                expires_at,
+                tags,
+                hide_results,
+                lazy_tally,
+                vote_token,
+                tie_break,
+                actions.map(|actions| *actions),
+            )
+        }
+        ReceiveMsg::FundReward { poll_id } => {
+            let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+            let sponsor = deps.api.addr_validate(&cw20_msg.sender)?;
+            // The sender is the token contract
+            let tokens = RewardPool {
+                native: vec![],
+                cw20: vec![Cw20CoinVerified {
+                    address: info.sender,
+                    amount: cw20_msg.amount,
+                }],
+            };
+            fund_reward(deps.storage, sponsor, poll_id, tokens)
+        }
+        ReceiveMsg::Vote { poll_id, option } => {
+            let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+            let voter = deps.api.addr_validate(&cw20_msg.sender)?;
+            let config = CONFIG.load(deps.storage)?;
+            check_vote_cooldown(deps.storage, &config, &env, &voter)?;
+            // The sender is the token contract
+            let tokens = Cw20CoinVerified {
+                address: info.sender,
+                amount: cw20_msg.amount,
+            };
+            let previous = cast_vote(
+                deps.branch(),
+                &env,
+                voter.clone(),
+                poll_id.clone(),
+                option.clone(),
+                &[],
+                Some(&tokens),
+            )?;
+            let response = vote_response(
+                deps.storage,
+                &env,
+                &config,
+                &poll_id,
+                &voter,
+                &option,
+                previous,
+            )?;
+            Ok(response.add_attribute("tokens", tokens.amount))
+        }
     }
 }
 
-fn execute_create_poll(
+// Next free number from NEXT_POLL_ID, checking the slug can become an alias of
+// it. Both are taken by create_poll once the poll is valid
+fn assign_poll_id(storage: &dyn Storage, slug: Option<&str>) -> Result<String, ContractError> {
+    // Polls created before slugs may have taken some numbers already
+    let mut next = NEXT_POLL_ID.load(storage)?;
+    while poll_id_in_use(storage, &next.to_string())? {
+        next += 1;
+    }
+    if let Some(slug) = slug {
+        // A number would hide the poll it is the id of
+        if proposal_id(slug).is_some() {
+            return Err(ContractError::NumericSlug {
+                slug: slug.to_string(),
+            });
+        }
+        // The slug of a deleted poll stays until its ballots are pruned, polls
+        // created before slugs kept theirs as id
+        let aliased = match SLUGS.may_load(storage, slug.to_string())? {
+            Some(poll_id) => poll_id_in_use(storage, &poll_id)?,
+            None => false,
+        };
+        if aliased || POLLS.has(storage, slug) {
+            return Err(ContractError::PollIdTaken {
+                poll_id: slug.to_string(),
+            });
+        }
+    }
+    Ok(next.to_string())
+}
+
+// Ballots not pruned yet would count in a new poll with the same id
+fn poll_id_in_use(storage: &dyn Storage, poll_id: &str) -> StdResult<bool> {
+    let ballots_left = VOTERS
+        .prefix(poll_id.to_string())
+        .keys(storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+    Ok(ballots_left || POLLS.has(storage, poll_id))
+}
+
+// Poll a message points to, by number or by slug
+fn resolve_poll_id(storage: &dyn Storage, poll_id: PollId) -> StdResult<String> {
+    let poll_id = String::from(poll_id);
+    Ok(SLUGS.may_load(storage, poll_id.clone())?.unwrap_or(poll_id))
+}
+
+fn resolve_poll_ids(storage: &dyn Storage, poll_ids: Vec<PollId>) -> StdResult<Vec<String>> {
+    poll_ids
+        .into_iter()
+        .map(|poll_id| resolve_poll_id(storage, poll_id))
+        .collect()
+}
+
+// Validates and stores a new poll, creation fees are handled by the callers
+#[allow(clippy::too_many_arguments)]
+fn create_poll(
     deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
+    env: Env,
+    creator: Addr,
     poll_id: String,
+    slug: Option<String>,
     question: String,
     options: Vec<String>,
+    veto: Option<String>,
+    vote_deposit: Option<Coin>,
+    expires_at: Option<Timestamp>,
+    tags: Option<Vec<String>>,
+    hide_results: Option<bool>,
+    lazy_tally: Option<bool>,
+    vote_token: Option<String>,
+    tie_break: Option<TieBreak>,
+    actions: Option<PollActions>,
 ) -> Result<Response, ContractError> {
+    ensure_not_banned(deps.storage, &creator)?;
+    let config = CONFIG.load(deps.storage)?;
+    if !config.allow_public_creation && !has_role(deps.storage, &creator, Role::Moderator)? {
+        return Err(ContractError::Unauthorized {
+            sender: creator.to_string(),
+        });
+    }
+
     // Restricts # of options for creating the poll
-    if options.len() > 5 {
-        return Err(ContractError::TooManyOptions {});
+    if options.len() < config.min_options as usize {
+        return Err(ContractError::TooFewOptions {
+            min: config.min_options,
+        });
+    }
+    if options.len() > config.max_options as usize {
+        return Err(ContractError::TooManyOptions {
+            max: config.max_options,
+        });
+    }
+    if question.len() > config.max_question_length as usize {
+        return Err(ContractError::QuestionTooLong {
+            max: config.max_question_length,
+        });
+    }
+    // Votes and tallies are keyed by the label
+    for (i, option) in options.iter().enumerate() {
+        if option.len() > config.max_option_length as usize {
+            return Err(ContractError::OptionTooLong {
+                option: option.clone(),
+                max: config.max_option_length,
+            });
+        }
+        if options[..i].contains(option) {
+            return Err(ContractError::DuplicateOption {
+                option: option.clone(),
+            });
+        }
+    }
+
+    // Counted until the poll is closed, vetoed or removed
+    let open_polls = OPEN_POLLS
+        .may_load(deps.storage, creator.clone())?
+        .unwrap_or_default();
+    if let Some(max) = config.max_open_polls_per_creator {
+        if open_polls >= max {
+            return Err(ContractError::TooManyOpenPolls { max });
+        }
+    }
+    OPEN_POLLS.save(deps.storage, creator.clone(), &(open_polls + 1))?;
+
+    let veto = veto.map(|veto| deps.api.addr_validate(&veto)).transpose()?;
+    let vote_token = vote_token
+        .map(|token| deps.api.addr_validate(&token))
+        .transpose()?;
+    // Token votes are sent by the token contract, without native coins
+    if vote_token.is_some() && vote_deposit.is_some() {
+        return Err(ContractError::VoteDepositWithToken {});
+    }
+    if let Some(actions) = &actions {
+        // Sent by the contract, which holds the funds of everyone. Only the
+        // owner, who can withdraw the fees anyway
+        if !cw_ownable::is_owner(deps.storage, &creator)? {
+            return Err(ContractError::Unauthorized {
+                sender: creator.to_string(),
+            });
+        }
+        // Voters get the whole voting period, see execute_close_poll
+        if expires_at.is_none() {
+            return Err(ContractError::NoExpiration { poll_id });
+        }
+        if !options.contains(&actions.option) {
+            return Err(ContractError::OptionNotFound {
+                option: actions.option.clone(),
+                poll_id,
+            });
+        }
+        if actions
+            .threshold
+            .is_some_and(|threshold| threshold > Decimal::one())
+        {
+            return Err(ContractError::InvalidThreshold {});
+        }
+    }
+    let tie_break = tie_break.unwrap_or_default();
+    if tie_break == TieBreak::Random && config.nois_proxy.is_none() {
+        return Err(ContractError::RandomnessUnavailable {});
+    }
+    if expires_at.is_some_and(|expires_at| expires_at <= env.block.time) {
+        return Err(ContractError::InvalidExpiration {});
+    }
+    let mut tags = tags.unwrap_or_default();
+    tags.sort();
+    tags.dedup();
+    if tags.len() > MAX_TAGS {
+        return Err(ContractError::TooManyTags { max: MAX_TAGS });
     }
 
-    // Generates a vector for the options to make the register of votes later
-    let mut opts: Vec<(String, u64)> = vec![];
-    for option in options {
-        opts.push((option, 0));
+    // Every option starts with no votes, so past heights of the poll can be queried
+    for option in &options {
+        TALLIES.save(
+            deps.storage,
+            (poll_id.clone(), option.clone()),
+            &Uint128::zero(),
+            env.block.height,
+        )?;
     }
 
     // Generates the poll
+    let options = options
+        .into_iter()
+        .enumerate()
+        .map(|(id, label)| PollOption {
+            id: id as u8,
+            label,
+        })
+        .collect();
+    // Token polls keep weighing ballots by the tokens sent
+    let dao = config.dao.clone().filter(|_| vote_token.is_none());
     let poll = Poll {
-        creator: info.sender,
+        creator,
         question,
-        options: opts,
+        options,
+        status: PollStatus::Open,
+        veto,
+        vote_deposit,
+        expires_at,
+        total_votes: 0,
+        voter_count: 0,
+        created_at: env.block.time,
+        created_height: env.block.height,
+        tags,
+        hide_results: hide_results.unwrap_or(false),
+        lazy_tally: lazy_tally.unwrap_or(false),
+        vote_token,
+        tie_break,
+        tie_winner: None,
+        dao,
+        slug,
     };
 
-    POLLS.save(deps.storage, poll_id, &poll)?;
+    if let Some(number) = proposal_id(&poll_id) {
+        NEXT_POLL_ID.save(deps.storage, &(number + 1))?;
+    }
+    if let Some(slug) = &poll.slug {
+        SLUGS.save(deps.storage, slug.clone(), &poll_id)?;
+    }
+    POLLS.save(deps.storage, &poll_id, &poll)?;
+    if let Some(actions) = &actions {
+        POLL_ACTIONS.save(deps.storage, poll_id.clone(), actions)?;
+    }
+    STATS.update(deps.storage, |mut stats| -> StdResult<_> {
+        stats.total_polls += 1;
+        Ok(stats)
+    })?;
+    POLL_COUNT.update(deps.storage, |count| -> StdResult<_> { Ok(count + 1) })?;
 
-    Ok(Response::new())
+    let event = evt_poll_created(&poll_id, &poll);
+    let expires_at = expiration_attribute(&poll);
+    let hooks = poll_hook_msgs(
+        deps.storage,
+        PollHookMsg::PollCreated {
+            poll_id: poll_id.clone(),
+            creator: poll.creator.to_string(),
+        },
+    )?;
+    Ok(Response::new()
+        .add_submessages(hooks)
+        .set_data(to_binary(&CreatePollResponse {
+            poll_id: poll_id.clone(),
+            poll: poll.clone(),
+        })?)
+        .add_attribute("action", "create_poll")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("creator", poll.creator)
+        .add_attribute("options", poll.options.len().to_string())
+        .add_attribute("expires_at", expires_at)
+        .add_event(event))
 }
 
 fn execute_vote(
-    deps: DepsMut,
-    _env: Env,
+    mut deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     poll_id: String,
     vote: String,
+    on_behalf_of: Option<String>,
 ) -> Result<Response, ContractError> {
-    let poll = POLLS.may_load(deps.storage, poll_id.clone())?;
+    let voter = match on_behalf_of {
+        // The sender must be the authorized proxy of the voter
+        Some(on_behalf_of) => {
+            let voter = deps.api.addr_validate(&on_behalf_of)?;
+            let proxy = VOTE_PROXIES.may_load(deps.storage, voter.clone())?;
+            if proxy.as_ref() != Some(&info.sender) {
+                return Err(ContractError::Unauthorized {
+                    sender: info.sender.to_string(),
+                });
+            }
+            voter
+        }
+        None => info.sender,
+    };
 
-    match poll {
-        // Poll exists
-        Some(mut poll) => {
-            BALLOTS.update(
-                deps.storage,
-                (info.sender, poll_id.clone()),
-                |ballot| -> StdResult<Ballot> {
-                    match ballot {
-                        Some(ballot) => {
-                            // Check if user has already voted
-                            let position_of_old_vote = poll
-                                .options
-                                .iter()
-                                .position(|option| option.0 == ballot.option)
-                                .unwrap();
-                            poll.options[position_of_old_vote].1 -= 1;
-                            Ok(Ballot {
-                                option: vote.clone(),
-                            })
-                        }
-                        None => Ok(Ballot {
-                            option: vote.clone(),
-                        }),
-                    }
-                },
-            )?;
+    let config = CONFIG.load(deps.storage)?;
+    check_vote_cooldown(deps.storage, &config, &env, &voter)?;
+    let previous = cast_vote(
+        deps.branch(),
+        &env,
+        voter.clone(),
+        poll_id.clone(),
+        vote.clone(),
+        &info.funds,
+        None,
+    )?;
+    Ok(vote_response(
+        deps.storage,
+        &env,
+        &config,
+        &poll_id,
+        &voter,
+        &vote,
+        previous,
+    )?)
+}
 
-            // Find the position of the new vote option and increment it by 1
-            let position = poll.options.iter().position(|option| option.0 == vote);
-            if position.is_none() {
-                return Err(ContractError::Unauthorized {});
-            }
-            let position = position.unwrap();
-            poll.options[position].1 += 1;
+// Attributes and events of a vote, previous being the ballot it replaced
+fn vote_response(
+    storage: &dyn Storage,
+    env: &Env,
+    config: &Config,
+    poll_id: &str,
+    voter: &Addr,
+    vote: &str,
+    previous: Option<Ballot>,
+) -> StdResult<Response> {
+    let mut response = Response::new()
+        .add_attribute("action", "vote")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("voter", voter)
+        .add_attribute("option", vote);
+    // Turnout and vote switching are told apart, repeating the same vote is neither
+    let (event, ballot_event) = match previous.as_ref().map(|ballot| ballot.option.as_str()) {
+        None => (
+            evt_vote(poll_id, voter, vote, None),
+            Some(evt_ballot_cast(poll_id, voter, vote)),
+        ),
+        Some(previous) if previous != vote => {
+            response = response.add_attribute("previous_option", previous);
+            (
+                evt_vote(poll_id, voter, vote, Some(previous)),
+                Some(evt_ballot_changed(poll_id, voter, previous, vote)),
+            )
+        }
+        Some(_) => (evt_vote(poll_id, voter, vote, None), None),
+    };
+    if config.debug_events {
+        let attributes = debug_vote_attributes(storage, poll_id, voter, vote, previous.as_ref())?;
+        response = response.add_attributes(attributes);
+    }
+    if previous.is_none() {
+        response = response.add_submessages(first_ballot_msgs(env, config, poll_id, voter)?);
+    }
+    let hooks = vote_hook_msgs(storage, poll_id, voter, vote, previous.as_ref())?;
+    Ok(response
+        .add_submessages(hooks)
+        .add_event(event)
+        .add_events(ballot_event))
+}
+
+// Mints the "I Voted" NFT and the participation token of a first ballot. A
+// failed mint is only reported by the reply, it doesn't revert the vote
+#[cfg_attr(not(feature = "tokenfactory"), allow(unused_variables))]
+fn first_ballot_msgs(
+    env: &Env,
+    config: &Config,
+    poll_id: &str,
+    voter: &Addr,
+) -> StdResult<Vec<SubMsg>> {
+    let mut msgs = vec![];
+    if let Some(nft) = &config.voted_nft {
+        let msg = WasmMsg::Execute {
+            contract_addr: nft.to_string(),
+            msg: to_binary(&Cw721ExecuteMsg::Mint {
+                token_id: format!("{poll_id}/{voter}"),
+                owner: voter.to_string(),
+                token_uri: None,
+                extension: None,
+            })?,
+            funds: vec![],
+        };
+        msgs.push(SubMsg::reply_on_error(msg, VOTED_NFT_REPLY_ID));
+    }
+    #[cfg(feature = "tokenfactory")]
+    if let Some(denom) = &config.participation_denom {
+        let msg = crate::tokenfactory::mint_msg(&env.contract.address, denom, 1, voter);
+        msgs.push(SubMsg::reply_on_error(msg, PARTICIPATION_REPLY_ID));
+    }
+    Ok(msgs)
+}
 
-            // This stores the updated vote
-            POLLS.save(deps.storage, poll_id, &poll)?;
-            Ok(Response::new())
+// Tally of the voted option around the vote and the counters of the poll. Read
+// again only in debug mode so normal votes don't pay for them
+fn debug_vote_attributes(
+    storage: &dyn Storage,
+    poll_id: &str,
+    voter: &Addr,
+    option: &str,
+    previous: Option<&Ballot>,
+) -> StdResult<Vec<(&'static str, String)>> {
+    let poll = POLLS.load(storage, poll_id)?;
+    let tally = TALLIES
+        .may_load(storage, (poll_id.to_string(), option.to_string()))?
+        .unwrap_or_default();
+    let weight = BALLOTS.load(storage, (voter, poll_id))?.weight;
+    // What the vote added to the option, lazy polls leave the tallies as they were
+    let moved = match previous {
+        _ if poll.lazy_tally => Uint128::zero(),
+        Some(previous) if previous.option == option => weight.saturating_sub(previous.weight),
+        _ => weight,
+    };
+    Ok(vec![
+        (
+            "debug_tally_before",
+            tally.saturating_sub(moved).to_string(),
+        ),
+        ("debug_tally_after", tally.to_string()),
+        ("debug_total_votes", poll.total_votes.to_string()),
+        ("debug_voter_count", poll.voter_count.to_string()),
+        ("debug_options", poll.options.len().to_string()),
+    ])
+}
+
+fn execute_set_vote_proxy(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    proxy: Option<String>,
+) -> Result<Response, ContractError> {
+    let res = Response::new()
+        .add_attribute("action", "set_vote_proxy")
+        .add_attribute("address", info.sender.as_str());
+    match proxy {
+        Some(proxy) => {
+            let proxy = deps.api.addr_validate(&proxy)?;
+            VOTE_PROXIES.save(deps.storage, info.sender, &proxy)?;
+            Ok(res.add_attribute("proxy", proxy))
+        }
+        None => {
+            VOTE_PROXIES.remove(deps.storage, info.sender);
+            Ok(res.add_attribute("proxy", "none"))
         }
-        // Poll doesn't exist
-        None => Err(ContractError::PollNotFound {}), // Return error
     }
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::AllPolls {} => query_all_polls(deps, env),
-        QueryMsg::Poll { poll_id } => query_poll(deps, env, poll_id),
-        QueryMsg::Vote { address, poll_id } => query_vote(deps, env, address, poll_id),
+fn execute_close_poll(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    let poll = load_poll(deps.storage, &poll_id)?;
+    if info.sender != poll.creator && !has_role(deps.storage, &info.sender, Role::Manager)? {
+        return Err(ContractError::NotPollCreator {
+            sender: info.sender.to_string(),
+            poll_id,
+        });
+    }
+    if poll.status != PollStatus::Open {
+        return Err(ContractError::PollClosed { poll_id });
+    }
+    // Polls sending messages can't be cut short
+    if POLL_ACTIONS.has(deps.storage, poll_id.clone())
+        && !matches!(poll.expires_at, Some(expires_at) if env.block.time >= expires_at)
+    {
+        return Err(ContractError::PollNotExpired { poll_id });
+    }
+
+    // Closing someone else's poll is an intervention
+    if info.sender != poll.creator {
+        audit(
+            deps.storage,
+            &env,
+            &info.sender,
+            "close_poll",
+            Some(&poll_id),
+        )?;
     }
+    finalize_poll(deps.storage, &env, &info.sender, poll_id, poll, info.funds)
 }
 
-fn query_all_polls(deps: Deps, _env: Env) -> StdResult<Binary> {
-    let polls = POLLS
-        .range(deps.storage, None, None, Order::Ascending) // Iterating
-        .map(|p| Ok(p?.1)) // The content is a function like the ones in the crash course
-        .collect::<StdResult<Vec<_>>>()?; // Stores it in a vector
+fn execute_finalize_expired(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    let poll = load_poll(deps.storage, &poll_id)?;
+    if poll.status != PollStatus::Open {
+        return Err(ContractError::PollClosed { poll_id });
+    }
+    let expired = matches!(poll.expires_at, Some(expires_at) if env.block.time >= expires_at);
+    if !expired {
+        return Err(ContractError::PollNotExpired { poll_id });
+    }
+    // A task that ran is over, nothing to cancel
+    if CONFIG.load(deps.storage)?.croncat_tasks.as_ref() == Some(&info.sender) {
+        FINALIZE_TASKS.remove(deps.storage, poll_id.clone());
+    }
+    finalize_poll(deps.storage, &env, &info.sender, poll_id, poll, info.funds)
+}
 
-    to_binary(&AllPollsResponse { polls })
+// Closes an open poll, funds paying the randomness of a tie break
+fn finalize_poll(
+    storage: &mut dyn Storage,
+    env: &Env,
+    closed_by: &Addr,
+    poll_id: String,
+    mut poll: Poll,
+    funds: Vec<Coin>,
+) -> Result<Response, ContractError> {
+    if poll.lazy_tally {
+        count_ballots(storage, env, &poll_id, &poll)?;
+    }
+    end_poll(storage, &mut poll, PollStatus::Closed)?;
+    POLLS.save(storage, &poll_id, &poll)?;
+    // The ballots as they are now, for VoteAtHeight
+    BALLOTS.add_checkpoint(storage, env.block.height + 1)?;
+    // The outcome, for automation reacting to the close
+    let tallies = load_tallies(storage, &poll_id, &poll)?;
+    let (winner, tie) = poll_winner(&poll, &tallies);
+    let winner_votes = tallies
+        .iter()
+        .find(|(option, _)| Some(option) == winner.as_ref())
+        .map(|(_, votes)| *votes)
+        .unwrap_or_default();
+    let hooks = poll_hook_msgs(
+        storage,
+        PollHookMsg::PollClosed {
+            poll_id: poll_id.clone(),
+            winner: winner.clone(),
+        },
+    )?;
+    let mut res = Response::new()
+        .add_submessages(cancel_finalize_task(storage, &poll_id)?)
+        .add_submessages(hooks)
+        .add_event(evt_poll_closed(
+            &poll_id,
+            closed_by,
+            winner.as_deref(),
+            winner_votes,
+            poll.total_votes,
+        ));
+    res = add_passed_actions(res, storage, &poll_id, winner.as_deref(), &tallies)?;
+    // The sent funds pay the randomness, without a proxy anymore the tie stays.
+    // Otherwise nothing takes them
+    let proxy = match (tie, poll.tie_break) {
+        (true, TieBreak::Random) => CONFIG.load(storage)?.nois_proxy,
+        _ => None,
+    };
+    if proxy.is_none() && !funds.is_empty() {
+        return Err(ContractError::UnexpectedFunds {});
+    }
+    if let Some(proxy) = proxy {
+        let job_id = request_randomness(
+            storage,
+            RandomnessJob::TieBreak {
+                poll_id: poll_id.clone(),
+            },
+        )?;
+        res = res
+            .add_attribute("tie_break_job_id", &job_id)
+            .add_message(WasmMsg::Execute {
+                contract_addr: proxy.to_string(),
+                msg: to_binary(&NoisProxyExecuteMsg::GetNextRandomness { job_id })?,
+                funds,
+            });
+    }
+    Ok(res)
 }
 
-fn query_poll(deps: Deps, _env: Env, poll_id: String) -> StdResult<Binary> {
-    let poll = POLLS.may_load(deps.storage, poll_id)?; // Gets the poll with commented id
-    to_binary(&PollResponse { poll })
+// Sends the actions of a proposal poll won by their option with the quorum and
+// threshold met. A failing message is only reported by the reply, the poll
+// stays closed
+fn add_passed_actions(
+    res: Response,
+    storage: &dyn Storage,
+    poll_id: &str,
+    winner: Option<&str>,
+    tallies: &[(String, Uint128)],
+) -> StdResult<Response> {
+    let Some(actions) = POLL_ACTIONS.may_load(storage, poll_id.to_string())? else {
+        return Ok(res);
+    };
+    if !actions_passed(&actions, winner, tallies) {
+        return Ok(res.add_attribute("passed", "false"));
+    }
+    let msgs = actions
+        .msgs
+        .into_iter()
+        .map(|msg| SubMsg::reply_on_error(msg, ACTIONS_REPLY_ID));
+    Ok(res.add_attribute("passed", "true").add_submessages(msgs))
 }
 
-fn query_vote(deps: Deps, _env: Env, address: String, poll_id: String) -> StdResult<Binary> {
-    let validated_address = deps.api.addr_validate(&address).unwrap(); // Address
-    let vote = BALLOTS.may_load(deps.storage, (validated_address, poll_id))?; // vote
+// Won by the option of the actions, with the quorum and threshold met
+fn actions_passed(
+    actions: &PollActions,
+    winner: Option<&str>,
+    tallies: &[(String, Uint128)],
+) -> bool {
+    let total: Uint128 = tallies.iter().map(|(_, votes)| *votes).sum();
+    let votes = tallies
+        .iter()
+        .find(|(option, _)| *option == actions.option)
+        .map(|(_, votes)| *votes)
+        .unwrap_or_default();
+    // Winning implies votes, the share can be computed
+    winner == Some(actions.option.as_str())
+        && total >= actions.quorum.unwrap_or_default()
+        && Decimal::from_ratio(votes, total) >= actions.threshold.unwrap_or_default()
+}
 
-    to_binary(&VoteResponse { vote }) // Return vote
+fn execute_veto_poll(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    let mut poll = load_poll(deps.storage, &poll_id)?;
+    if poll.veto.as_ref() != Some(&info.sender)
+        && !has_role(deps.storage, &info.sender, Role::Manager)?
+    {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender.to_string(),
+        });
+    }
+    // The veto only applies before finalization
+    if poll.status != PollStatus::Open {
+        return Err(ContractError::PollClosed { poll_id });
+    }
+
+    end_poll(deps.storage, &mut poll, PollStatus::Vetoed)?;
+    POLLS.save(deps.storage, &poll_id, &poll)?;
+    audit(
+        deps.storage,
+        &env,
+        &info.sender,
+        "veto_poll",
+        Some(&poll_id),
+    )?;
+    // A vetoed poll has no winner, hooks see it closed like any other
+    let hooks = poll_hook_msgs(
+        deps.storage,
+        PollHookMsg::PollClosed {
+            poll_id: poll_id.clone(),
+            winner: None,
+        },
+    )?;
+    Ok(Response::new()
+        .add_attribute("action", "veto_poll")
+        .add_attribute("poll_id", &poll_id)
+        .add_attribute("sender", info.sender.as_str())
+        .add_submessages(cancel_finalize_task(deps.storage, &poll_id)?)
+        .add_submessages(hooks)
+        .add_event(evt_poll_closed(
+            &poll_id,
+            &info.sender,
+            None,
+            Uint128::zero(),
+            poll.total_votes,
+        )))
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::contract::{execute, instantiate, query}; // Adding execute
-    use crate::msg::{
-        AllPollsResponse, ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg, VoteResponse,
-    }; // Adding ExecuteMsg
-       // use crate::ContractError;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{attr, from_binary}; // constructs an attribute // mock functions
+fn execute_schedule_finalize(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    let create = finalize_task_msg(deps.storage, &env, &poll_id, info.funds)?;
+    Ok(Response::new().add_submessage(create))
+}
 
-    // Fake addresses
-    pub const ADDR1: &str = "addr1";
-    pub const ADDR2: &str = "addr2";
+// Creation of the CronCat task sending FinalizeExpired at the expiration of the
+// poll, paid with funds
+fn finalize_task_msg(
+    storage: &mut dyn Storage,
+    env: &Env,
+    poll_id: &str,
+    funds: Vec<Coin>,
+) -> Result<SubMsg, ContractError> {
+    let poll_id = poll_id.to_string();
+    let croncat = CONFIG
+        .load(storage)?
+        .croncat_tasks
+        .ok_or(ContractError::SchedulerUnavailable {})?;
+    let poll = load_poll(storage, &poll_id)?;
+    if poll.status != PollStatus::Open {
+        return Err(ContractError::PollClosed { poll_id });
+    }
+    // CronCat wouldn't pay the randomness of a tie
+    if poll.tie_break == TieBreak::Random {
+        return Err(ContractError::RandomTieBreakScheduled { poll_id });
+    }
+    let expires_at = poll.expires_at.ok_or_else(|| ContractError::NoExpiration {
+        poll_id: poll_id.clone(),
+    })?;
+    if FINALIZE_TASKS.has(storage, poll_id.clone()) {
+        return Err(ContractError::FinalizeScheduled { poll_id });
+    }
 
-    #[test]
-    fn test_instantiate() {
-        let mut deps = mock_dependencies();
-        // Mock the contract environment (like a real chain)
-        let env = mock_env();
-        // Mock the message info, ADDR1 will be the sender, the empty vec means we sent no funds.
-        let info = mock_info(ADDR1, &[]);
+    let finalize = WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        msg: to_binary(&ExecuteMsg::FinalizeExpired {
+            poll_id: poll_id.parse()?,
+        })?,
+        funds: vec![],
+    };
+    let task = CroncatTaskRequest {
+        interval: CroncatInterval::Once,
+        boundary: Some(CroncatBoundary::Time {
+            start: Some(expires_at),
+            end: None,
+        }),
+        stop_on_fail: true,
+        actions: vec![CroncatAction {
+            msg: finalize.into(),
+            gas_limit: Some(FINALIZE_GAS_LIMIT),
+        }],
+    };
+    PENDING_FINALIZE_TASK.save(storage, &poll_id)?;
+    // CronCat refunds what the task didn't use to its owner, this contract
+    let create = WasmMsg::Execute {
+        contract_addr: croncat.to_string(),
+        msg: to_binary(&CroncatExecuteMsg::CreateTask {
+            task: Box::new(task),
+        })?,
+        funds,
+    };
+    Ok(SubMsg::reply_on_success(create, FINALIZE_TASK_REPLY_ID))
+}
 
-        // Create a message where the sender will be an admin
-        let msg = InstantiateMsg { admin: None };
-        // Call instantiate, unwrap to assert success
-        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+// Removal of the CronCat task of a poll finalized before its expiration. A
+// task CronCat already dropped doesn't revert the finalization
+fn cancel_finalize_task(storage: &mut dyn Storage, poll_id: &str) -> StdResult<Vec<SubMsg>> {
+    let (Some(task_hash), Some(croncat)) = (
+        FINALIZE_TASKS.may_load(storage, poll_id.to_string())?,
+        CONFIG.load(storage)?.croncat_tasks,
+    ) else {
+        return Ok(vec![]);
+    };
+    FINALIZE_TASKS.remove(storage, poll_id.to_string());
+    let remove = WasmMsg::Execute {
+        contract_addr: croncat.to_string(),
+        msg: to_binary(&CroncatExecuteMsg::RemoveTask { task_hash })?,
+        funds: vec![],
+    };
+    Ok(vec![SubMsg::reply_on_error(remove, CANCEL_TASK_REPLY_ID)])
+}
+
+// Tallies of a lazy poll from all of its ballots at once
+fn count_ballots(
+    storage: &mut dyn Storage,
+    env: &Env,
+    poll_id: &str,
+    poll: &Poll,
+) -> Result<(), ContractError> {
+    let mut tallies: Vec<_> = poll
+        .options
+        .iter()
+        .map(|option| (option.label.clone(), Uint128::zero()))
+        .collect();
+    let voters = VOTERS
+        .prefix(poll_id.to_string())
+        .keys(storage, None, None, Order::Ascending);
+    for voter in voters {
+        let ballot = BALLOTS.load(storage, (&voter?, poll_id))?;
+        let corruption = || ContractError::TallyCorruption {
+            poll_id: poll_id.to_string(),
+            option: ballot.option.clone(),
+        };
+        let (_, votes) = tallies
+            .iter_mut()
+            .find(|(option, _)| *option == ballot.option)
+            .ok_or_else(corruption)?;
+        *votes = votes.checked_add(ballot.weight).map_err(|_| corruption())?;
+    }
+    for (option, votes) in tallies {
+        TALLIES.save(
+            storage,
+            (poll_id.to_string(), option),
+            &votes,
+            env.block.height,
+        )?;
+    }
+    Ok(())
+}
+
+// Moves poll to a final status, releasing the open poll slot of its creator
+fn end_poll(storage: &mut dyn Storage, poll: &mut Poll, status: PollStatus) -> StdResult<()> {
+    if matches!(poll.status, PollStatus::Open | PollStatus::Paused) {
+        OPEN_POLLS.update(
+            storage,
+            poll.creator.clone(),
+            |open_polls| -> StdResult<_> { Ok(open_polls.unwrap_or_default().saturating_sub(1)) },
+        )?;
+    }
+    poll.status = status;
+    Ok(())
+}
+
+fn execute_flag_poll(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    poll_id: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    let mut poll = load_poll(deps.storage, &poll_id)?;
+    if poll.status != PollStatus::Open {
+        return Err(ContractError::PollClosed { poll_id });
+    }
+    let key = (poll_id.clone(), info.sender);
+    if FLAGS.has(deps.storage, key.clone()) {
+        return Err(ContractError::AlreadyFlagged {
+            poll_id,
+            sender: key.1.to_string(),
+        });
+    }
+    FLAGS.save(deps.storage, key, &reason)?;
+    let flags = FLAG_COUNTS.update(deps.storage, poll_id.clone(), |flags| -> StdResult<_> {
+        Ok(flags.unwrap_or_default() + 1)
+    })?;
+
+    // Enough flags pause the poll until a moderator resumes or removes it
+    let config = CONFIG.load(deps.storage)?;
+    if config
+        .flag_threshold
+        .is_some_and(|threshold| flags >= threshold)
+    {
+        poll.status = PollStatus::Paused;
+        POLLS.save(deps.storage, &poll_id, &poll)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "flag_poll")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("flags", flags.to_string())
+        .add_attribute("paused", (poll.status == PollStatus::Paused).to_string()))
+}
+
+fn execute_resume_poll(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    if !has_role(deps.storage, &info.sender, Role::Moderator)? {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender.to_string(),
+        });
+    }
+    let mut poll = load_poll(deps.storage, &poll_id)?;
+    if poll.status != PollStatus::Paused {
+        return Err(ContractError::PollNotPaused { poll_id });
+    }
+
+    // The review starts a fresh round of flags
+    let flaggers = FLAGS
+        .prefix(poll_id.clone())
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for flagger in flaggers {
+        FLAGS.remove(deps.storage, (poll_id.clone(), flagger));
+    }
+    FLAG_COUNTS.remove(deps.storage, poll_id.clone());
+
+    poll.status = PollStatus::Open;
+    POLLS.save(deps.storage, &poll_id, &poll)?;
+    audit(
+        deps.storage,
+        &env,
+        &info.sender,
+        "resume_poll",
+        Some(&poll_id),
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "resume_poll")
+        .add_attribute("poll_id", poll_id))
+}
+
+fn execute_remove_poll(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    if !has_role(deps.storage, &info.sender, Role::Moderator)? {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender.to_string(),
+        });
+    }
+    remove_poll(deps.storage, &env, info.sender, poll_id, reason)
+}
+
+fn remove_poll(
+    storage: &mut dyn Storage,
+    env: &Env,
+    moderator: Addr,
+    poll_id: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    let mut poll = load_poll(storage, &poll_id)?;
+    if poll.status == PollStatus::Removed {
+        return Err(ContractError::PollRemoved { poll_id });
+    }
+
+    end_poll(storage, &mut poll, PollStatus::Removed)?;
+    POLLS.save(storage, &poll_id, &poll)?;
+    let removal = Removal {
+        moderator,
+        reason,
+        removed_at: env.block.time,
+    };
+    REMOVALS.save(storage, poll_id.clone(), &removal)?;
+    audit(
+        storage,
+        env,
+        &removal.moderator,
+        "remove_poll",
+        Some(&poll_id),
+    )?;
+
+    let slashed = slash_creation_deposit(storage, &poll_id)?;
+
+    Ok(Response::new()
+        .add_submessages(cancel_finalize_task(storage, &poll_id)?)
+        .add_attribute("action", "remove_poll")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("moderator", removal.moderator)
+        .add_attribute("reason", removal.reason)
+        .add_attribute("slashed", slashed))
+}
+
+fn execute_emergency_remove(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_ids: Vec<String>,
+) -> Result<Response, ContractError> {
+    if !has_role(deps.storage, &info.sender, Role::Manager)? {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender.to_string(),
+        });
+    }
+
+    let mut msgs = vec![];
+    for poll_id in &poll_ids {
+        let mut poll = load_poll(deps.storage, poll_id)?;
+        slash_creation_deposit(deps.storage, poll_id)?;
+        // Rewards are split with the tallies as they were
+        msgs.extend(clear_poll_state(deps.storage, poll_id, &poll)?);
+        end_poll(deps.storage, &mut poll, PollStatus::Removed)?;
+
+        // Ballots can be too many for one transaction, they are left to
+        // PruneBallots. Vote deposits stay claimable by their voters
+        let flaggers = FLAGS
+            .prefix(poll_id.clone())
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for flagger in flaggers {
+            FLAGS.remove(deps.storage, (poll_id.clone(), flagger));
+        }
+        FLAG_COUNTS.remove(deps.storage, poll_id.clone());
+        for option in poll.options {
+            TALLIES.remove(
+                deps.storage,
+                (poll_id.clone(), option.label),
+                env.block.height,
+            )?;
+        }
+        POLLS.remove(deps.storage, poll_id)?;
+        audit(
+            deps.storage,
+            &env,
+            &info.sender,
+            "emergency_remove",
+            Some(poll_id),
+        )?;
+        POLL_COUNT.update(deps.storage, |count| -> StdResult<_> {
+            Ok(count.saturating_sub(1))
+        })?;
+    }
+
+    Ok(Response::new()
+        .add_submessages(msgs)
+        .add_attribute("action", "emergency_remove")
+        .add_attribute("poll_ids", poll_ids.join(",")))
+}
+
+// Everything kept by poll_id besides the ballots, before the poll is deleted,
+// so a new poll taking the id starts clean. What the contract still holds for
+// the poll goes back to who sent it
+fn clear_poll_state(
+    storage: &mut dyn Storage,
+    poll_id: &str,
+    poll: &Poll,
+) -> Result<Vec<SubMsg>, ContractError> {
+    let mut msgs = cancel_finalize_task(storage, poll_id)?;
+    POLL_ACTIONS.remove(storage, poll_id.to_string());
+    REMOVALS.remove(storage, poll_id.to_string());
+
+    // What the slash left of the creation deposit
+    if let Some(deposit) = CREATION_DEPOSITS.may_load(storage, poll_id.to_string())? {
+        CREATION_DEPOSITS.remove(storage, poll_id.to_string());
+        msgs.push(SubMsg::new(BankMsg::Send {
+            to_address: poll.creator.to_string(),
+            amount: vec![deposit],
+        }));
+    }
+
+    // Sponsors get back the part of the rewards voters didn't claim yet
+    if REWARD_POOLS.has(storage, poll_id.to_string()) {
+        let total: Uint128 = match ensure_tallied(poll) {
+            Ok(()) => load_tallies(storage, poll_id, poll)?
+                .into_iter()
+                .map(|(_, votes)| votes)
+                .sum(),
+            Err(_) => Uint128::zero(),
+        };
+        let claimants = REWARD_CLAIMS
+            .prefix(poll_id.to_string())
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        let mut claimed = Uint128::zero();
+        for claimant in claimants {
+            claimed += BALLOTS.load(storage, (&claimant, poll_id))?.weight;
+            REWARD_CLAIMS.remove(storage, (poll_id.to_string(), claimant));
+        }
+        let sponsors = REWARD_SPONSORS
+            .prefix(poll_id.to_string())
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (sponsor, sponsored) in sponsors {
+            REWARD_SPONSORS.remove(storage, (poll_id.to_string(), sponsor.clone()));
+            let refund = match claimed.is_zero() {
+                true => sponsored,
+                false => sponsored.share(total - claimed, total),
+            };
+            msgs.extend(reward_msgs(&sponsor, refund)?.into_iter().map(SubMsg::new));
+        }
+        REWARD_POOLS.remove(storage, poll_id.to_string());
+    }
+
+    // An undrawn lottery is called off, its randomness won't be used
+    if let Some(lottery) = LOTTERIES.may_load(storage, poll_id.to_string())? {
+        LOTTERIES.remove(storage, poll_id.to_string());
+        if lottery.drawn.is_none() {
+            msgs.push(SubMsg::new(BankMsg::Send {
+                to_address: lottery.sponsor.to_string(),
+                amount: vec![Coin {
+                    denom: lottery.prize.denom,
+                    amount: lottery
+                        .prize
+                        .amount
+                        .checked_mul(Uint128::from(lottery.winners))
+                        .map_err(StdError::from)?,
+                }],
+            }));
+        }
+    }
+    let jobs = RANDOMNESS_JOBS
+        .range(storage, None, None, Order::Ascending)
+        .filter(|job| match job {
+            Ok((_, RandomnessJob::Lottery { poll_id: id }))
+            | Ok((_, RandomnessJob::TieBreak { poll_id: id })) => id == poll_id,
+            Err(_) => true,
+        })
+        .map(|job| job.map(|(job_id, _)| job_id))
+        .collect::<StdResult<Vec<_>>>()?;
+    for job_id in jobs {
+        RANDOMNESS_JOBS.remove(storage, job_id);
+    }
+    Ok(msgs)
+}
+
+fn execute_prune_ballots(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    poll_id: String,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    if POLLS.has(deps.storage, &poll_id) {
+        return Err(ContractError::PollNotDeleted {});
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let voters = VOTERS
+        .prefix(poll_id.clone())
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    for voter in &voters {
+        let ballot = BALLOTS.load(deps.storage, (voter, &poll_id))?;
+        OPTION_VOTERS.remove(
+            deps.storage,
+            (poll_id.clone(), ballot.option, voter.clone()),
+        );
+        BALLOTS.remove(deps.storage, (voter, &poll_id), env.block.height)?;
+        VOTERS.remove(deps.storage, (poll_id.clone(), voter.clone()));
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "prune_ballots")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("pruned", voters.len().to_string()))
+}
+
+// Sends the slashed part of the creation deposit of a removed poll to the treasury,
+// the creator can reclaim the rest
+fn slash_creation_deposit(storage: &mut dyn Storage, poll_id: &str) -> StdResult<Uint128> {
+    let mut deposit = match CREATION_DEPOSITS.may_load(storage, poll_id.to_string())? {
+        Some(deposit) => deposit,
+        None => return Ok(Uint128::zero()),
+    };
+    let config = CONFIG.load(storage)?;
+    let slashed = deposit.amount * config.deposit_slash_ratio;
+    FEES.update(storage, deposit.denom.clone(), |fees| -> StdResult<_> {
+        Ok(fees.unwrap_or_default() + slashed)
+    })?;
+    deposit.amount -= slashed;
+    if deposit.amount.is_zero() {
+        CREATION_DEPOSITS.remove(storage, poll_id.to_string());
+    } else {
+        CREATION_DEPOSITS.save(storage, poll_id.to_string(), &deposit)?;
+    }
+    Ok(slashed)
+}
+
+fn execute_claim_deposit(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    // Polls deleted by an emergency removal are over too
+    let poll = POLLS.may_load(deps.storage, &poll_id)?;
+    // Paused polls can still be resumed
+    if poll.is_some_and(|poll| matches!(poll.status, PollStatus::Open | PollStatus::Paused)) {
+        return Err(ContractError::PollOpen { poll_id });
+    }
+
+    let key = (info.sender.clone(), poll_id);
+    let deposit = DEPOSITS.may_load(deps.storage, key.clone())?;
+    let escrow = VOTE_ESCROW.may_load(deps.storage, key.clone())?;
+    if deposit.is_none() && escrow.is_none() {
+        return Err(ContractError::NoDeposit { poll_id: key.1 });
+    }
+    DEPOSITS.remove(deps.storage, key.clone());
+    VOTE_ESCROW.remove(deps.storage, key.clone());
+
+    let mut res = Response::new()
+        .add_attribute("action", "claim_deposit")
+        .add_attribute("poll_id", key.1)
+        .add_attribute("voter", info.sender.as_str());
+    if let Some(deposit) = deposit {
+        res = res
+            .add_attribute("deposit", deposit.to_string())
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![deposit],
+            });
+    }
+    // Tokens sent as vote weight
+    if let Some(escrow) = escrow {
+        res = res
+            .add_attribute("escrow_token", escrow.address.as_str())
+            .add_attribute("escrow_amount", escrow.amount)
+            .add_message(WasmMsg::Execute {
+                contract_addr: escrow.address.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: info.sender.to_string(),
+                    amount: escrow.amount,
+                })?,
+                funds: vec![],
+            });
+    }
+    Ok(res)
+}
+
+fn fund_reward(
+    storage: &mut dyn Storage,
+    sponsor: Addr,
+    poll_id: String,
+    funds: RewardPool,
+) -> Result<Response, ContractError> {
+    let poll = load_poll(storage, &poll_id)?;
+    if poll.status != PollStatus::Open {
+        return Err(ContractError::PollClosed { poll_id });
+    }
+
+    let mut pool = REWARD_POOLS
+        .may_load(storage, poll_id.clone())?
+        .unwrap_or_default();
+    pool.add(funds.clone());
+    REWARD_POOLS.save(storage, poll_id.clone(), &pool)?;
+    let key = (poll_id.clone(), sponsor.clone());
+    let mut sponsored = REWARD_SPONSORS
+        .may_load(storage, key.clone())?
+        .unwrap_or_default();
+    sponsored.add(funds);
+    REWARD_SPONSORS.save(storage, key, &sponsored)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "fund_reward")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("sponsor", sponsor))
+}
+
+fn execute_claim_reward(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    poll_ids: Vec<String>,
+) -> Result<Response, ContractError> {
+    let mut payout = RewardPool::default();
+    let mut claimed = 0;
+    for poll_id in poll_ids {
+        if let Some(reward) = claim_reward(deps.storage, &info.sender, poll_id)? {
+            payout.add(reward);
+            claimed += 1;
+        }
+    }
+    if payout.is_empty() {
+        return Err(ContractError::NoReward {});
+    }
+
+    Ok(Response::new()
+        .add_messages(reward_msgs(&info.sender, payout)?)
+        .add_attribute("action", "claim_reward")
+        .add_attribute("claimant", &info.sender)
+        .add_attribute("polls", claimed.to_string()))
+}
+
+// Transfers of a reward to recipient, one for the coins and one per cw20 token
+fn reward_msgs(recipient: &Addr, pool: RewardPool) -> StdResult<Vec<CosmosMsg>> {
+    let mut msgs = vec![];
+    if !pool.native.is_empty() {
+        msgs.push(
+            BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: pool.native,
+            }
+            .into(),
+        );
+    }
+    for coin in pool.cw20 {
+        msgs.push(
+            WasmMsg::Execute {
+                contract_addr: coin.address.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount: coin.amount,
+                })?,
+                funds: vec![],
+            }
+            .into(),
+        );
+    }
+    Ok(msgs)
+}
+
+// Reward of claimant from one poll, marking it paid. None if there is nothing
+// left for it
+fn claim_reward(
+    storage: &mut dyn Storage,
+    claimant: &Addr,
+    poll_id: String,
+) -> Result<Option<RewardPool>, ContractError> {
+    let Some(pool) = REWARD_POOLS.may_load(storage, poll_id.clone())? else {
+        return Ok(None);
+    };
+    // Polls deleted by an emergency removal are over too
+    match POLLS.may_load(storage, &poll_id)? {
+        Some(poll) if matches!(poll.status, PollStatus::Open | PollStatus::Paused) => {
+            return Err(ContractError::PollOpen { poll_id });
+        }
+        Some(poll) if poll.status == PollStatus::Closed => {
+            let total: Uint128 = load_tallies(storage, &poll_id, &poll)?
+                .into_iter()
+                .map(|(_, votes)| votes)
+                .sum();
+            // Without votes the pool goes back to the sponsors
+            if !total.is_zero() {
+                let key = (poll_id.clone(), claimant.clone());
+                if REWARD_CLAIMS.has(storage, key.clone()) {
+                    return Ok(None);
+                }
+                let Some(ballot) = BALLOTS.may_load(storage, (claimant, &poll_id))? else {
+                    return Ok(None);
+                };
+                REWARD_CLAIMS.save(storage, key, &Empty {})?;
+                return Ok(Some(pool.share(ballot.weight, total)));
+            }
+        }
+        _ => {}
+    }
+
+    let key = (poll_id, claimant.clone());
+    let sponsored = REWARD_SPONSORS.may_load(storage, key.clone())?;
+    REWARD_SPONSORS.remove(storage, key);
+    Ok(sponsored)
+}
+
+fn execute_start_lottery(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    poll_id: String,
+    winners: u32,
+    prize: Coin,
+) -> Result<Response, ContractError> {
+    let proxy = CONFIG
+        .load(deps.storage)?
+        .nois_proxy
+        .ok_or(ContractError::RandomnessUnavailable {})?;
+    let poll = load_poll(deps.storage, &poll_id)?;
+    if info.sender != poll.creator && !has_role(deps.storage, &info.sender, Role::Manager)? {
+        return Err(ContractError::NotPollCreator {
+            sender: info.sender.to_string(),
+            poll_id,
+        });
+    }
+    match poll.status {
+        PollStatus::Open | PollStatus::Paused => return Err(ContractError::PollOpen { poll_id }),
+        PollStatus::Removed => return Err(ContractError::PollRemoved { poll_id }),
+        PollStatus::Closed | PollStatus::Vetoed => {}
+    }
+    if winners == 0 {
+        return Err(ContractError::InvalidWinners {});
+    }
+    if LOTTERIES.has(deps.storage, poll_id.clone()) {
+        return Err(ContractError::LotteryExists { poll_id });
+    }
+    // No more winners than voters
+    let winners = winners.min(poll.voter_count.try_into().unwrap_or(u32::MAX));
+    if winners == 0 {
+        return Err(ContractError::NoVoters { poll_id });
+    }
+
+    let prizes = prize
+        .amount
+        .checked_mul(Uint128::from(winners))
+        .map_err(StdError::from)?;
+    let paid = info
+        .funds
+        .iter()
+        .find(|coin| coin.denom == prize.denom)
+        .map(|coin| coin.amount)
+        .unwrap_or_default();
+    if paid < prizes {
+        return Err(ContractError::InsufficientFee {
+            expected: Coin {
+                denom: prize.denom,
+                amount: prizes,
+            },
+        });
+    }
+    // What is left pays the proxy
+    let fee: Vec<Coin> = info
+        .funds
+        .into_iter()
+        .map(|mut coin| {
+            if coin.denom == prize.denom {
+                coin.amount -= prizes;
+            }
+            coin
+        })
+        .filter(|coin| !coin.amount.is_zero())
+        .collect();
+
+    let job_id = request_randomness(
+        deps.storage,
+        RandomnessJob::Lottery {
+            poll_id: poll_id.clone(),
+        },
+    )?;
+    LOTTERIES.save(
+        deps.storage,
+        poll_id.clone(),
+        &Lottery {
+            sponsor: info.sender,
+            winners,
+            prize,
+            drawn: None,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "start_lottery")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("job_id", &job_id)
+        .add_attribute("winners", winners.to_string())
+        .add_message(WasmMsg::Execute {
+            contract_addr: proxy.to_string(),
+            msg: to_binary(&NoisProxyExecuteMsg::GetNextRandomness { job_id })?,
+            funds: fee,
+        }))
+}
+
+// Records a job waiting for randomness, returning its id
+fn request_randomness(storage: &mut dyn Storage, job: RandomnessJob) -> StdResult<String> {
+    let job_id = NEXT_JOB_ID.may_load(storage)?.unwrap_or(1);
+    NEXT_JOB_ID.save(storage, &(job_id + 1))?;
+    let job_id = job_id.to_string();
+    RANDOMNESS_JOBS.save(storage, job_id.clone(), &job)?;
+    Ok(job_id)
+}
+
+fn execute_nois_receive(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    callback: NoisCallback,
+) -> Result<Response, ContractError> {
+    if CONFIG.load(deps.storage)?.nois_proxy.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender.to_string(),
+        });
+    }
+    let job = RANDOMNESS_JOBS
+        .may_load(deps.storage, callback.job_id.clone())?
+        .ok_or(ContractError::UnknownRandomnessJob {
+            job_id: callback.job_id.clone(),
+        })?;
+    RANDOMNESS_JOBS.remove(deps.storage, callback.job_id);
+
+    match job {
+        RandomnessJob::Lottery { poll_id } => {
+            draw_lottery(deps.storage, poll_id, callback.randomness.as_slice())
+        }
+        RandomnessJob::TieBreak { poll_id } => {
+            break_tie(deps.storage, poll_id, callback.randomness.as_slice())
+        }
+    }
+}
+
+// Picks the winner among the tied leaders from a hash of the randomness
+fn break_tie(
+    storage: &mut dyn Storage,
+    poll_id: String,
+    randomness: &[u8],
+) -> Result<Response, ContractError> {
+    let mut poll = load_poll(storage, &poll_id)?;
+    let tallies = load_tallies(storage, &poll_id, &poll)?;
+    let top = tallies
+        .iter()
+        .map(|(_, votes)| *votes)
+        .max()
+        .unwrap_or_default();
+    let mut leaders: Vec<_> = tallies
+        .iter()
+        .filter(|(_, votes)| *votes == top)
+        .map(|(option, _)| option.clone())
+        .collect();
+    let hash = Sha256::digest(randomness);
+    let number = u64::from_be_bytes(hash[..8].try_into().unwrap());
+    let winner = leaders.swap_remove((number % leaders.len() as u64) as usize);
+
+    poll.tie_winner = Some(winner.clone());
+    POLLS.save(storage, &poll_id, &poll)?;
+    let res = Response::new()
+        .add_attribute("action", "break_tie")
+        .add_attribute("poll_id", &poll_id)
+        .add_attribute("winner", &winner);
+    // Removed since it was closed, the draw doesn't count
+    let (winner, _) = poll_winner(&poll, &tallies);
+    Ok(add_passed_actions(
+        res,
+        storage,
+        &poll_id,
+        winner.as_deref(),
+        &tallies,
+    )?)
+}
+
+// Picks the winners one at a time, each from a hash of the randomness and the
+// round so anyone can check the draw
+fn draw_lottery(
+    storage: &mut dyn Storage,
+    poll_id: String,
+    randomness: &[u8],
+) -> Result<Response, ContractError> {
+    let mut lottery = LOTTERIES.load(storage, poll_id.clone())?;
+    let mut voters = VOTERS
+        .prefix(poll_id.clone())
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut drawn = vec![];
+    for round in 0..lottery.winners {
+        if voters.is_empty() {
+            break;
+        }
+        let hash = Sha256::new()
+            .chain_update(randomness)
+            .chain_update(round.to_be_bytes())
+            .finalize();
+        let number = u64::from_be_bytes(hash[..8].try_into().unwrap());
+        drawn.push(voters.swap_remove((number % voters.len() as u64) as usize));
+    }
+
+    let mut res = Response::new()
+        .add_attribute("action", "draw_lottery")
+        .add_attribute("poll_id", &poll_id)
+        .add_attribute(
+            "winners",
+            drawn.iter().map(Addr::as_str).collect::<Vec<_>>().join(","),
+        );
+    for winner in &drawn {
+        res = res.add_message(BankMsg::Send {
+            to_address: winner.to_string(),
+            amount: vec![lottery.prize.clone()],
+        });
+    }
+    // Removed ballots can leave fewer voters than when the lottery started
+    let missing = lottery.winners - drawn.len() as u32;
+    if missing > 0 {
+        res = res.add_message(BankMsg::Send {
+            to_address: lottery.sponsor.to_string(),
+            amount: vec![Coin {
+                denom: lottery.prize.denom.clone(),
+                amount: lottery
+                    .prize
+                    .amount
+                    .checked_mul(Uint128::from(missing))
+                    .map_err(StdError::from)?,
+            }],
+        });
+    }
+    lottery.drawn = Some(drawn);
+    LOTTERIES.save(storage, poll_id, &lottery)?;
+    Ok(res)
+}
+
+fn execute_add_hook(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    if !has_role(deps.storage, &info.sender, Role::Manager)? {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender.to_string(),
+        });
+    }
+    let hook = deps.api.addr_validate(&addr)?;
+    if !HOOKS.add_hook(deps.storage, hook)? {
+        return Err(ContractError::HookAlreadyRegistered { hook: addr });
+    }
+    audit(deps.storage, &env, &info.sender, "add_hook", None)?;
+    Ok(Response::new()
+        .add_attribute("action", "add_hook")
+        .add_attribute("hook", addr))
+}
+
+fn execute_remove_hook(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    if !has_role(deps.storage, &info.sender, Role::Manager)? {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender.to_string(),
+        });
+    }
+    let hook = deps.api.addr_validate(&addr)?;
+    if !HOOKS.remove_hook(deps.storage, &hook)? {
+        return Err(ContractError::HookNotRegistered { hook: addr });
+    }
+    audit(deps.storage, &env, &info.sender, "remove_hook", None)?;
+    Ok(Response::new()
+        .add_attribute("action", "remove_hook")
+        .add_attribute("hook", addr))
+}
+
+// Registers or unregisters a hook of the DAO DAO proposal module, only the DAO
+fn execute_dao_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    hooks: &Hooks,
+    address: String,
+    add: bool,
+) -> Result<Response, ContractError> {
+    if CONFIG.load(deps.storage)?.dao.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender.to_string(),
+        });
+    }
+    let hook = deps.api.addr_validate(&address)?;
+    let action = if add {
+        if !hooks.add_hook(deps.storage, hook)? {
+            return Err(ContractError::HookAlreadyRegistered { hook: address });
+        }
+        "add_dao_hook"
+    } else {
+        if !hooks.remove_hook(deps.storage, &hook)? {
+            return Err(ContractError::HookNotRegistered { hook: address });
+        }
+        "remove_dao_hook"
+    };
+    Ok(Response::new()
+        .add_attribute("action", action)
+        .add_attribute("hook", address))
+}
+
+// Addresses registered in hooks, for the hook queries
+fn hooks_response(storage: &dyn Storage, hooks: &Hooks) -> StdResult<HooksResponse> {
+    Ok(HooksResponse {
+        hooks: hooks
+            .hooks(storage)?
+            .into_iter()
+            .map(String::from)
+            .collect(),
+    })
+}
+
+// msg sent to every hook, a failure is only reported by the reply
+fn poll_hook_msgs(storage: &dyn Storage, msg: PollHookMsg) -> StdResult<Vec<SubMsg>> {
+    let mut msgs = dao_hook_msgs(storage, &msg)?;
+    let msg = to_binary(&PollHookExecuteMsg::PollHook(msg))?;
+    msgs.extend(HOOKS.prepare_hooks(storage, |hook| Ok(hook_submsg(&hook, msg.clone())))?);
+    Ok(msgs)
+}
+
+fn hook_submsg(hook: &Addr, msg: Binary) -> SubMsg {
+    let execute = WasmMsg::Execute {
+        contract_addr: hook.to_string(),
+        msg,
+        funds: vec![],
+    };
+    SubMsg::reply_on_error(execute, HOOK_REPLY_ID)
+}
+
+// Same events for the DAO DAO hooks, for the polls of a DAO with a proposal id
+fn dao_hook_msgs(storage: &dyn Storage, msg: &PollHookMsg) -> StdResult<Vec<SubMsg>> {
+    let (poll_id, hooks) = match msg {
+        PollHookMsg::Voted { poll_id, .. } => (poll_id, &VOTE_HOOKS),
+        PollHookMsg::PollCreated { poll_id, .. } | PollHookMsg::PollClosed { poll_id, .. } => {
+            (poll_id, &PROPOSAL_HOOKS)
+        }
+    };
+    let Some(id) = proposal_id(poll_id) else {
+        return Ok(vec![]);
+    };
+    // Usually none, checked before loading the poll
+    if hooks.hooks(storage)?.is_empty() {
+        return Ok(vec![]);
+    }
+    let poll = POLLS.load(storage, poll_id)?;
+    if poll.dao.is_none() {
+        return Ok(vec![]);
+    }
+    let msg = match msg {
+        PollHookMsg::PollCreated { creator, .. } => {
+            DaoHookExecuteMsg::ProposalHook(ProposalHookMsg::NewProposal {
+                id,
+                proposer: creator.clone(),
+            })
+        }
+        PollHookMsg::Voted { voter, option, .. } => {
+            DaoHookExecuteMsg::VoteHook(VoteHookMsg::NewVote {
+                proposal_id: id,
+                voter: voter.clone(),
+                vote: option.clone(),
+            })
+        }
+        PollHookMsg::PollClosed { .. } => {
+            DaoHookExecuteMsg::ProposalHook(ProposalHookMsg::ProposalStatusChanged {
+                id,
+                old_status: Cw3Status::Open.as_str().to_string(),
+                new_status: cw3_status(storage, poll_id, &poll)?.as_str().to_string(),
+            })
+        }
+    };
+    let msg = to_binary(&msg)?;
+    hooks.prepare_hooks(storage, |hook| Ok(hook_submsg(&hook, msg.clone())))
+}
+
+// Number of an auto numbered poll, "001" isn't proposal 1
+fn proposal_id(poll_id: &str) -> Option<u64> {
+    poll_id
+        .parse::<u64>()
+        .ok()
+        .filter(|id| id.to_string() == poll_id)
+}
+
+// Hook messages of a ballot, none if it repeats the previous one
+fn vote_hook_msgs(
+    storage: &dyn Storage,
+    poll_id: &str,
+    voter: &Addr,
+    vote: &str,
+    previous: Option<&Ballot>,
+) -> StdResult<Vec<SubMsg>> {
+    let previous_option = previous.map(|ballot| ballot.option.clone());
+    if previous_option.as_deref() == Some(vote) {
+        return Ok(vec![]);
+    }
+    poll_hook_msgs(
+        storage,
+        PollHookMsg::Voted {
+            poll_id: poll_id.to_string(),
+            voter: voter.to_string(),
+            option: vote.to_string(),
+            previous_option,
+        },
+    )
+}
+
+fn execute_grant_role(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    role: Role,
+) -> Result<Response, ContractError> {
+    if !can_manage_role(deps.storage, &info.sender, role)? {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender.to_string(),
+        });
+    }
+    let address = deps.api.addr_validate(&address)?;
+    // Replacing a role is like revoking the current one
+    if let Some(current) = ROLES.may_load(deps.storage, address.clone())? {
+        if !can_manage_role(deps.storage, &info.sender, current)? {
+            return Err(ContractError::Unauthorized {
+                sender: info.sender.to_string(),
+            });
+        }
+    }
+
+    ROLES.save(deps.storage, address.clone(), &role)?;
+    audit(deps.storage, &env, &info.sender, "grant_role", None)?;
+    Ok(Response::new()
+        .add_attribute("action", "grant_role")
+        .add_attribute("role", role.as_str())
+        .add_attribute("address", address))
+}
+
+fn execute_revoke_role(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    let address = deps.api.addr_validate(&address)?;
+    let role = ROLES
+        .may_load(deps.storage, address.clone())?
+        .ok_or(ContractError::NoRole {})?;
+    if !can_manage_role(deps.storage, &info.sender, role)? {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender.to_string(),
+        });
+    }
+
+    ROLES.remove(deps.storage, address.clone());
+    audit(deps.storage, &env, &info.sender, "revoke_role", None)?;
+    Ok(Response::new()
+        .add_attribute("action", "revoke_role")
+        .add_attribute("role", role.as_str())
+        .add_attribute("address", address))
+}
+
+fn execute_update_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: cw_ownable::Action,
+) -> Result<Response, ContractError> {
+    let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
+    Ok(Response::new().add_attributes(ownership.into_attributes()))
+}
+
+fn execute_update_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    update: ConfigUpdate,
+) -> Result<Response, ContractError> {
+    if !has_role(deps.storage, &info.sender, Role::Manager)? {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender.to_string(),
+        });
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
+    if let Some(min_options) = update.min_options {
+        config.min_options = min_options;
+    }
+    if let Some(max_options) = update.max_options {
+        config.max_options = max_options;
+    }
+    if let Some(allow_public_creation) = update.allow_public_creation {
+        config.allow_public_creation = allow_public_creation;
+    }
+    if let Some(vote_cooldown) = update.vote_cooldown {
+        config.vote_cooldown = Some(vote_cooldown).filter(|cooldown| *cooldown > 0);
+    }
+    if let Some(creation_fee) = update.creation_fee {
+        config.creation_fee = Some(creation_fee).filter(|fee| !fee.amount.is_zero());
+    }
+    if let Some(cw20_creation_fee) = update.cw20_creation_fee {
+        config.cw20_creation_fee =
+            Some(fee_to_verified(deps.api, cw20_creation_fee)?).filter(|fee| !fee.amount.is_zero());
+    }
+    if let Some(creation_deposit) = update.creation_deposit {
+        config.creation_deposit =
+            Some(creation_deposit).filter(|deposit| !deposit.amount.is_zero());
+    }
+    if let Some(deposit_slash_ratio) = update.deposit_slash_ratio {
+        config.deposit_slash_ratio = deposit_slash_ratio;
+    }
+    if let Some(flag_threshold) = update.flag_threshold {
+        config.flag_threshold = Some(flag_threshold).filter(|threshold| *threshold > 0);
+    }
+    if let Some(max_open_polls_per_creator) = update.max_open_polls_per_creator {
+        config.max_open_polls_per_creator = Some(max_open_polls_per_creator).filter(|max| *max > 0);
+    }
+    if let Some(max_question_length) = update.max_question_length {
+        config.max_question_length = max_question_length;
+    }
+    if let Some(max_option_length) = update.max_option_length {
+        config.max_option_length = max_option_length;
+    }
+    if let Some(debug_events) = update.debug_events {
+        config.debug_events = debug_events;
+    }
+    if let Some(voted_nft) = update.voted_nft {
+        config.voted_nft = match voted_nft.is_empty() {
+            true => None,
+            false => Some(deps.api.addr_validate(&voted_nft)?),
+        };
+    }
+    if let Some(denom) = update.participation_denom {
+        if !denom.is_empty() && cfg!(not(feature = "tokenfactory")) {
+            return Err(ContractError::TokenfactoryUnsupported {});
+        }
+        config.participation_denom = Some(denom).filter(|denom| !denom.is_empty());
+    }
+    if let Some(nois_proxy) = update.nois_proxy {
+        config.nois_proxy = match nois_proxy.is_empty() {
+            true => None,
+            false => Some(deps.api.addr_validate(&nois_proxy)?),
+        };
+    }
+    if let Some(croncat_tasks) = update.croncat_tasks {
+        config.croncat_tasks = match croncat_tasks.is_empty() {
+            true => None,
+            false => Some(deps.api.addr_validate(&croncat_tasks)?),
+        };
+    }
+    if let Some(dao) = update.dao {
+        config.dao = match dao.is_empty() {
+            true => None,
+            false => Some(deps.api.addr_validate(&dao)?),
+        };
+    }
+    validate_option_bounds(&config)?;
+    validate_creation_funds(&config)?;
+    CONFIG.save(deps.storage, &config)?;
+    audit(deps.storage, &env, &info.sender, "update_config", None)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+fn execute_set_paused(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    if !has_role(deps.storage, &info.sender, Role::Manager)? {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender.to_string(),
+        });
+    }
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.paused = paused;
+        Ok(config)
+    })?;
+    let action = if paused { "pause" } else { "unpause" };
+    audit(deps.storage, &env, &info.sender, action, None)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_paused")
+        .add_attribute("paused", paused.to_string()))
+}
+
+fn execute_withdraw_fees(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    to: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let to = deps.api.addr_validate(&to)?;
+
+    let fees = load_fees(deps.storage)?;
+    let cw20_fees = load_cw20_fees(deps.storage)?;
+    if fees.is_empty() && cw20_fees.is_empty() {
+        return Err(ContractError::NoFees {});
+    }
+
+    let mut res = Response::new()
+        .add_attribute("action", "withdraw_fees")
+        .add_attribute("to", to.to_string());
+    if !fees.is_empty() {
+        for fee in &fees {
+            FEES.remove(deps.storage, fee.denom.clone());
+        }
+        res = res.add_message(BankMsg::Send {
+            to_address: to.to_string(),
+            amount: fees,
+        });
+    }
+    for fee in cw20_fees {
+        CW20_FEES.remove(deps.storage, fee.address.clone());
+        res = res.add_message(WasmMsg::Execute {
+            contract_addr: fee.address.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: to.to_string(),
+                amount: fee.amount,
+            })?,
+            funds: vec![],
+        });
+    }
+
+    Ok(res)
+}
+
+// Collected creation fees, sorted by denom
+fn load_fees(storage: &dyn Storage) -> StdResult<Vec<Coin>> {
+    FEES.range(storage, None, None, Order::Ascending)
+        .map(|fee| {
+            let (denom, amount) = fee?;
+            Ok(Coin { denom, amount })
+        })
+        .collect()
+}
+
+// Collected cw20 creation fees, sorted by token
+fn load_cw20_fees(storage: &dyn Storage) -> StdResult<Vec<Cw20CoinVerified>> {
+    CW20_FEES
+        .range(storage, None, None, Order::Ascending)
+        .map(|fee| {
+            let (address, amount) = fee?;
+            Ok(Cw20CoinVerified { address, amount })
+        })
+        .collect()
+}
+
+fn execute_ban_address(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    banned: bool,
+) -> Result<Response, ContractError> {
+    if !has_role(deps.storage, &info.sender, Role::Moderator)? {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender.to_string(),
+        });
+    }
+
+    let address = deps.api.addr_validate(&address)?;
+    if banned {
+        BANNED.save(deps.storage, address.clone(), &Empty {})?;
+    } else {
+        BANNED.remove(deps.storage, address.clone());
+    }
+    let action = if banned { "ban" } else { "unban" };
+    audit(deps.storage, &env, &info.sender, action, None)?;
+
+    Ok(Response::new()
+        .add_attribute("action", action)
+        .add_attribute("address", address))
+}
+
+fn ensure_not_banned(storage: &dyn Storage, address: &Addr) -> Result<(), ContractError> {
+    if BANNED.has(storage, address.clone()) {
+        return Err(ContractError::Banned {
+            address: address.to_string(),
+        });
+    }
+    Ok(())
+}
+
+// Options a poll needs at least and can have at most, within MAX_OPTIONS
+fn validate_option_bounds(config: &Config) -> Result<(), ContractError> {
+    if config.min_options == 0
+        || config.min_options > config.max_options
+        || config.max_options > MAX_OPTIONS
+    {
+        return Err(ContractError::InvalidOptionBounds {});
+    }
+    Ok(())
+}
+
+// Creation fee and deposit are paid together, so they must share the denom
+fn validate_creation_funds(config: &Config) -> Result<(), ContractError> {
+    if config.deposit_slash_ratio > Decimal::one() {
+        return Err(ContractError::InvalidSlashRatio {});
+    }
+    if let (Some(fee), Some(deposit)) = (&config.creation_fee, &config.creation_deposit) {
+        if fee.denom != deposit.denom {
+            return Err(ContractError::MismatchedDenoms {});
+        }
+    }
+    Ok(())
+}
+
+fn fee_to_verified(api: &dyn Api, fee: Cw20Coin) -> StdResult<Cw20CoinVerified> {
+    Ok(Cw20CoinVerified {
+        address: api.addr_validate(&fee.address)?,
+        amount: fee.amount,
+    })
+}
+
+fn load_poll(storage: &dyn Storage, poll_id: &str) -> Result<Poll, ContractError> {
+    POLLS
+        .may_load(storage, poll_id)?
+        .ok_or_else(|| ContractError::PollNotFound {
+            poll_id: poll_id.to_string(),
+        })
+}
+
+// Whether address is the owner or has at least the role given
+fn has_role(storage: &dyn Storage, address: &Addr, role: Role) -> StdResult<bool> {
+    if cw_ownable::is_owner(storage, address)? {
+        return Ok(true);
+    }
+    let current = ROLES.may_load(storage, address.clone())?;
+    Ok(current.is_some_and(|current| current >= role))
+}
+
+// Whether address can grant or revoke role, managers handle moderators and the
+// owner handles everyone
+fn can_manage_role(storage: &dyn Storage, address: &Addr, role: Role) -> StdResult<bool> {
+    match role {
+        Role::Moderator => has_role(storage, address, Role::Manager),
+        Role::Manager => cw_ownable::is_owner(storage, address),
+    }
+}
+
+// Appends an admin or moderator action to AUDIT_LOG
+fn audit(
+    storage: &mut dyn Storage,
+    env: &Env,
+    actor: &Addr,
+    action: &str,
+    poll_id: Option<&str>,
+) -> StdResult<()> {
+    let next = AUDIT_LOG
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(0, |last| last + 1);
+    let entry = AuditEntry {
+        actor: actor.clone(),
+        action: action.to_string(),
+        poll_id: poll_id.map(str::to_string),
+        height: env.block.height,
+        time: env.block.time,
+    };
+    AUDIT_LOG.save(storage, next, &entry)
+}
+
+fn execute_reclaim_deposit(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    let poll = load_poll(deps.storage, &poll_id)?;
+    if info.sender != poll.creator {
+        return Err(ContractError::NotPollCreator {
+            sender: info.sender.to_string(),
+            poll_id,
+        });
+    }
+    // Removed polls only keep what was not slashed
+    // Paused polls can still be resumed
+    if matches!(poll.status, PollStatus::Open | PollStatus::Paused) {
+        return Err(ContractError::PollOpen { poll_id });
+    }
+
+    let deposit = CREATION_DEPOSITS
+        .may_load(deps.storage, poll_id.clone())?
+        .ok_or_else(|| ContractError::NoDeposit {
+            poll_id: poll_id.to_string(),
+        })?;
+    CREATION_DEPOSITS.remove(deps.storage, poll_id.clone());
+
+    Ok(Response::new()
+        .add_attribute("action", "reclaim_deposit")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("creator", info.sender.as_str())
+        .add_attribute("amount", deposit.to_string())
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![deposit],
+        }))
+}
+
+fn execute_vote_many(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    votes: Vec<(String, String)>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info).map_err(|_| ContractError::UnexpectedFunds {})?;
+    // The whole batch counts as one vote action
+    let config = CONFIG.load(deps.storage)?;
+    check_vote_cooldown(deps.storage, &config, &env, &info.sender)?;
+    // Any failing vote returns an error, which reverts all the previous ones
+    let mut res = Response::new()
+        .add_attribute("action", "vote_many")
+        .add_attribute("voter", &info.sender)
+        .add_attribute("votes", votes.len().to_string());
+    for (i, (poll_id, vote)) in votes.iter().enumerate() {
+        if votes[..i].iter().any(|(voted, _)| voted == poll_id) {
+            return Err(ContractError::AlreadyVoted {
+                poll_id: poll_id.clone(),
+            });
+        }
+        // Polls requiring a vote deposit have to be voted individually
+        let previous = cast_vote(
+            deps.branch(),
+            &env,
+            info.sender.clone(),
+            poll_id.clone(),
+            vote.clone(),
+            &[],
+            None,
+        )?;
+        // Events of each vote, its attributes would repeat the same keys
+        let vote_res = vote_response(
+            deps.storage,
+            &env,
+            &config,
+            poll_id,
+            &info.sender,
+            vote,
+            previous,
+        )?;
+        res = res
+            .add_submessages(vote_res.messages)
+            .add_events(vote_res.events);
+    }
+    Ok(res)
+}
+
+fn execute_vote_by_signature(
+    mut deps: DepsMut,
+    env: Env,
+    poll_id: PollId,
+    option: String,
+    voter_pubkey: Binary,
+    signature: Binary,
+    nonce: u64,
+) -> Result<Response, ContractError> {
+    // The voter is whoever owns the key, not the relayer sending the transaction
+    let voter = pubkey_to_address(&env.contract.address, &voter_pubkey)?;
+
+    // Each signed vote can only be used once
+    let expected_nonce = VOTE_NONCES
+        .may_load(deps.storage, voter.clone())?
+        .unwrap_or_default();
+    if nonce != expected_nonce {
+        return Err(ContractError::InvalidNonce {
+            expected: expected_nonce,
+        });
+    }
+
+    // Signed with the poll id as sent, number or slug
+    let payload = to_vec(&SignedVotePayload {
+        contract: env.contract.address.to_string(),
+        poll_id: poll_id.to_string(),
+        option: option.clone(),
+        nonce,
+    })?;
+    let message_hash = Sha256::digest(adr036_sign_doc(&voter, &payload));
+    let verified = deps
+        .api
+        .secp256k1_verify(&message_hash, &signature, &voter_pubkey)
+        .map_err(|_| ContractError::InvalidSignature {})?;
+    if !verified {
+        return Err(ContractError::InvalidSignature {});
+    }
+
+    VOTE_NONCES.save(deps.storage, voter.clone(), &(nonce + 1))?;
+    let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+    let config = CONFIG.load(deps.storage)?;
+    check_vote_cooldown(deps.storage, &config, &env, &voter)?;
+    let previous = cast_vote(
+        deps.branch(),
+        &env,
+        voter.clone(),
+        poll_id.clone(),
+        option.clone(),
+        &[],
+        None,
+    )?;
+    let response = vote_response(
+        deps.storage,
+        &env,
+        &config,
+        &poll_id,
+        &voter,
+        &option,
+        previous,
+    )?;
+    Ok(response.add_attribute("nonce", nonce.to_string()))
+}
+
+// Throttles vote actions of voter when the contract has a vote cooldown
+fn check_vote_cooldown(
+    storage: &mut dyn Storage,
+    config: &Config,
+    env: &Env,
+    voter: &Addr,
+) -> Result<(), ContractError> {
+    if let Some(cooldown) = config.vote_cooldown {
+        if let Some(last_action) = LAST_ACTION.may_load(storage, voter.clone())? {
+            let ready_at = last_action.plus_seconds(cooldown);
+            if env.block.time < ready_at {
+                return Err(ContractError::VoteCooldown { ready_at });
+            }
+        }
+        LAST_ACTION.save(storage, voter.clone(), &env.block.time)?;
+    }
+    Ok(())
+}
+
+// Records the ballot of voter and moves the tallies of the poll accordingly,
+// funds are only used to pay the vote deposit of the poll
+fn cast_vote(
+    deps: DepsMut,
+    env: &Env,
+    voter: Addr,
+    poll_id: String,
+    vote: String,
+    funds: &[Coin],
+    tokens: Option<&Cw20CoinVerified>,
+) -> Result<Option<Ballot>, ContractError> {
+    let mut poll = load_poll(deps.storage, &poll_id)?;
+    match poll.status {
+        PollStatus::Open => {}
+        PollStatus::Paused => return Err(ContractError::PollPaused { poll_id }),
+        _ => return Err(ContractError::PollClosed { poll_id }),
+    }
+    if poll
+        .expires_at
+        .is_some_and(|expires_at| env.block.time >= expires_at)
+    {
+        return Err(ContractError::PollExpired { poll_id });
+    }
+    ensure_not_banned(deps.storage, &voter)?;
+    if !poll.has_option(&vote) {
+        return Err(ContractError::OptionNotFound {
+            option: vote,
+            poll_id,
+        });
+    }
+    // Token polls are only voted by sending the token
+    match (&poll.vote_token, tokens) {
+        (None, None) => {}
+        (Some(token), Some(tokens)) if *token == tokens.address => {}
+        (Some(token), None) => {
+            return Err(ContractError::TokenVoteRequired {
+                poll_id,
+                token: token.to_string(),
+            })
+        }
+        (_, Some(tokens)) => {
+            return Err(ContractError::UnsupportedToken {
+                token: tokens.address.to_string(),
+            })
+        }
+    }
+
+    // The deposit is paid once, with the first ballot
+    let mut deposit_due = None;
+    if let Some(deposit) = &poll.vote_deposit {
+        let key = (voter.clone(), poll_id.clone());
+        if !DEPOSITS.has(deps.storage, key.clone()) {
+            if !funds.contains(deposit) {
+                return Err(ContractError::DepositRequired {
+                    expected: deposit.clone(),
+                });
+            }
+            deposit_due = Some((key, deposit));
+        }
+    }
+    // Any other coin would get stuck in the contract
+    if funds.len() > usize::from(deposit_due.is_some()) {
+        return Err(ContractError::UnexpectedFunds {});
+    }
+    // Voting power in the DAO when the poll was created
+    let dao_power = match &poll.dao {
+        Some(dao) => {
+            let res: VotingPowerAtHeightResponse = deps.querier.query_wasm_smart(
+                dao,
+                &DaoQueryMsg::VotingPowerAtHeight {
+                    address: voter.to_string(),
+                    height: Some(poll.created_height),
+                },
+            )?;
+            if res.power.is_zero() {
+                return Err(ContractError::NoVotingPower { poll_id });
+            }
+            Some(res.power)
+        }
+        None => None,
+    };
+    // Nothing is written before this point, invalid votes leave the state untouched
+    if let Some((key, deposit)) = deposit_due {
+        DEPOSITS.save(deps.storage, key, deposit)?;
+    }
+    if let Some(tokens) = tokens {
+        let key = (voter.clone(), poll_id.clone());
+        VOTE_ESCROW.update(deps.storage, key, |escrow| -> StdResult<_> {
+            let mut escrow = escrow.unwrap_or(Cw20CoinVerified {
+                address: tokens.address.clone(),
+                amount: Uint128::zero(),
+            });
+            escrow.amount += tokens.amount;
+            Ok(escrow)
+        })?;
+    }
+
+    // One address one vote, token ballots weigh all the tokens sent so far and
+    // ballots of DAO polls the voting power
+    let previous = BALLOTS.may_load(deps.storage, (&voter, &poll_id))?;
+    let weight = match tokens {
+        Some(tokens) => previous
+            .as_ref()
+            .map_or(Uint128::zero(), |ballot| ballot.weight)
+            .checked_add(tokens.amount)
+            .map_err(StdError::from)?,
+        None => dao_power.unwrap_or_else(Uint128::one),
+    };
+    match &previous {
+        // Same option and weight again, nothing moves
+        Some(ballot) if ballot.option == vote && ballot.weight == weight => return Ok(previous),
+        Some(ballot) => {
+            OPTION_VOTERS.remove(
+                deps.storage,
+                (poll_id.clone(), ballot.option.clone(), voter.clone()),
+            );
+            // The ballot was counted in the tally, or the state is broken
+            if !poll.lazy_tally {
+                TALLIES.update(
+                    deps.storage,
+                    (poll_id.clone(), ballot.option.clone()),
+                    env.block.height,
+                    |votes| {
+                        votes
+                            .and_then(|votes| votes.checked_sub(ballot.weight).ok())
+                            .ok_or_else(|| ContractError::TallyCorruption {
+                                poll_id: poll_id.clone(),
+                                option: ballot.option.clone(),
+                            })
+                    },
+                )?;
+            }
+        }
+        None => {
+            poll.voter_count += 1;
+            let participation = PARTICIPATION
+                .may_load(deps.storage, voter.clone())?
+                .unwrap_or_default();
+            STATS.update(deps.storage, |mut stats| -> StdResult<_> {
+                stats.total_ballots += 1;
+                stats.unique_voters += u64::from(participation == 0);
+                Ok(stats)
+            })?;
+            PARTICIPATION.save(deps.storage, voter.clone(), &(participation + 1))?;
+            PARTICIPATION_RANKING.remove(deps.storage, (participation, voter.clone()));
+            PARTICIPATION_RANKING.save(
+                deps.storage,
+                (participation + 1, voter.clone()),
+                &Empty {},
+            )?;
+            VOTERS.save(deps.storage, (poll_id.clone(), voter.clone()), &Empty {})?;
+        }
+    }
+    // Every vote counts, changed ones too. Only the turnout of the poll changes,
+    // no index depends on it, they don't need to be rewritten
+    poll.total_votes += 1;
+    POLL_ENTRIES.save(deps.storage, &poll_id, &poll)?;
+    OPTION_VOTERS.save(
+        deps.storage,
+        (poll_id.clone(), vote.clone(), voter.clone()),
+        &Empty {},
+    )?;
+    BALLOTS.save(
+        deps.storage,
+        (&voter, &poll_id),
+        &Ballot {
+            option: vote.clone(),
+            weight,
+        },
+        env.block.height,
+    )?;
+    if poll.lazy_tally {
+        return Ok(previous);
+    }
+    TALLIES.update(
+        deps.storage,
+        (poll_id.clone(), vote.clone()),
+        env.block.height,
+        |votes| {
+            votes.unwrap_or_default().checked_add(weight).map_err(|_| {
+                ContractError::TallyCorruption {
+                    poll_id,
+                    option: vote,
+                }
+            })
+        },
+    )?;
+    Ok(previous)
+}
+
+// Derives the account address of a compressed secp256k1 public key, using the
+// bech32 prefix of the chain this contract lives on
+fn pubkey_to_address(contract: &Addr, pubkey: &Binary) -> Result<Addr, ContractError> {
+    // Compressed keys only, the uncompressed form of the same key would be
+    // another address voting again
+    if !matches!(pubkey.as_slice(), [0x02 | 0x03, rest @ ..] if rest.len() == 32) {
+        return Err(ContractError::InvalidPubkey {});
+    }
+    let prefix = contract
+        .as_str()
+        .rsplit_once('1')
+        .map(|(prefix, _)| prefix)
+        .ok_or(ContractError::InvalidPubkey {})?;
+    let hash = Ripemd160::digest(Sha256::digest(pubkey.as_slice()));
+    let address = bech32::encode(prefix, hash.to_base32(), Variant::Bech32)
+        .map_err(|_| ContractError::InvalidPubkey {})?;
+    Ok(Addr::unchecked(address))
+}
+
+// ADR-036 sign doc for arbitrary data, this is what wallets sign with `signArbitrary`
+fn adr036_sign_doc(signer: &Addr, data: &[u8]) -> Vec<u8> {
+    format!(
+        r#"{{"account_number":"0","chain_id":"","fee":{{"amount":[],"gas":"0"}},"memo":"","msgs":[{{"type":"sign/MsgSignData","value":{{"data":"{}","signer":"{}"}}}}],"sequence":"0"}}"#,
+        Binary::from(data).to_base64(),
+        signer
+    )
+    .into_bytes()
+}
+
+// Lets native chain governance step in without the admin key
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        // Recorded as removed by the contract itself
+        SudoMsg::RemovePoll { poll_id, reason } => {
+            let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+            let moderator = env.contract.address.clone();
+            remove_poll(deps.storage, &env, moderator, poll_id, reason)
+        }
+        SudoMsg::SetAdmin { admin } => sudo_set_admin(deps, admin),
+    }
+}
+
+fn sudo_set_admin(deps: DepsMut, admin: Option<String>) -> Result<Response, ContractError> {
+    // Replaces the owner and drops any pending transfer
+    let ownership = cw_ownable::initialize_owner(deps.storage, deps.api, admin.as_deref())?;
+    Ok(Response::new()
+        .add_attribute("action", "sudo_set_admin")
+        .add_attributes(ownership.into_attributes()))
+}
+
+// Exported as the query entry point, taking the cw3 queries besides QueryMsg
+pub mod entry {
+    use super::*;
+    use crate::msg::QueryEntryMsg;
+
+    #[cfg_attr(not(feature = "library"), entry_point)]
+    pub fn query(deps: Deps, env: Env, msg: QueryEntryMsg) -> StdResult<Binary> {
+        match msg {
+            QueryEntryMsg::Contract(msg) => super::query(deps, env, msg),
+            QueryEntryMsg::Cw3(msg) => query_cw3(deps, env, msg),
+        }
+    }
+}
+
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::AllPolls {
+            start_after,
+            limit,
+            order,
+        } => query_all_polls(deps, env, start_after, limit, order),
+        QueryMsg::PollsByCreator {
+            creator,
+            start_after,
+            limit,
+            order,
+        } => query_polls_by_creator(deps, env, creator, start_after, limit, order),
+        QueryMsg::PollsByStatus {
+            status,
+            start_after,
+            limit,
+            order,
+        } => query_polls_by_status(deps, env, status, start_after, limit, order),
+        QueryMsg::PollsByCreatorAndStatus {
+            creator,
+            status,
+            start_after,
+            limit,
+            order,
+        } => {
+            query_polls_by_creator_and_status(deps, env, creator, status, start_after, limit, order)
+        }
+        QueryMsg::PollsEndingBefore { timestamp, limit } => {
+            query_polls_ending_before(deps, env, timestamp, limit)
+        }
+        QueryMsg::PollsById { poll_ids } => {
+            query_polls_by_id(deps, env, resolve_poll_ids(deps.storage, poll_ids)?)
+        }
+        QueryMsg::RecentPolls { limit } => query_recent_polls(deps, env, limit),
+        QueryMsg::PollsByTag {
+            tag,
+            start_after,
+            limit,
+        } => query_polls_by_tag(deps, env, tag, start_after, limit),
+        QueryMsg::PollsWithPrefix { prefix, limit } => {
+            query_polls_with_prefix(deps, env, prefix, limit)
+        }
+        QueryMsg::OpenPollsNotVoted {
+            address,
+            start_after,
+            limit,
+        } => query_open_polls_not_voted(deps, env, address, start_after, limit),
+        QueryMsg::Poll { poll_id } => {
+            query_poll(deps, env, resolve_poll_id(deps.storage, poll_id)?)
+        }
+        QueryMsg::PollMetadata { poll_id } => {
+            query_poll_metadata(deps, env, resolve_poll_id(deps.storage, poll_id)?)
+        }
+        QueryMsg::PollExists { poll_id } => {
+            query_poll_exists(deps, env, resolve_poll_id(deps.storage, poll_id)?)
+        }
+        QueryMsg::PollResults { poll_id } => {
+            query_poll_results(deps, env, resolve_poll_id(deps.storage, poll_id)?)
+        }
+        QueryMsg::PollResultsWithPermit { poll_id, permit } => {
+            query_poll_results_with_permit(deps, env, poll_id, permit)
+        }
+        QueryMsg::Winner { poll_id } => {
+            query_winner(deps, env, resolve_poll_id(deps.storage, poll_id)?)
+        }
+        QueryMsg::TallyAtHeight { poll_id, height } => {
+            query_tally_at_height(deps, env, resolve_poll_id(deps.storage, poll_id)?, height)
+        }
+        QueryMsg::OptionTally { poll_id, option } => {
+            query_option_tally(deps, env, resolve_poll_id(deps.storage, poll_id)?, option)
+        }
+        QueryMsg::PollVoters {
+            poll_id,
+            start_after,
+            limit,
+        } => query_poll_voters(
+            deps,
+            env,
+            resolve_poll_id(deps.storage, poll_id)?,
+            start_after,
+            limit,
+        ),
+        QueryMsg::VotesByAddress {
+            address,
+            start_after,
+            limit,
+        } => query_votes_by_address(deps, env, address, start_after, limit),
+        QueryMsg::VotersByOption {
+            poll_id,
+            option,
+            start_after,
+            limit,
+        } => query_voters_by_option(
+            deps,
+            env,
+            resolve_poll_id(deps.storage, poll_id)?,
+            option,
+            start_after,
+            limit,
+        ),
+        QueryMsg::Participation { address } => query_participation(deps, env, address),
+        QueryMsg::TopVoters { limit } => query_top_voters(deps, env, limit),
+        QueryMsg::Vote { address, poll_id } => {
+            query_vote(deps, env, address, resolve_poll_id(deps.storage, poll_id)?)
+        }
+        QueryMsg::VoteAtHeight {
+            poll_id,
+            address,
+            height,
+        } => query_vote_at_height(
+            deps,
+            env,
+            address,
+            resolve_poll_id(deps.storage, poll_id)?,
+            height,
+        ),
+        QueryMsg::Ownership {} => to_binary(&cw_ownable::get_ownership(deps.storage)?),
+        QueryMsg::Config {} => query_config(deps, env),
+        QueryMsg::ContractInfo {} => query_contract_info(deps, env),
+        QueryMsg::Stats {} => to_binary(&STATS.load(deps.storage)?),
+        QueryMsg::Removal { poll_id } => {
+            query_removal(deps, env, resolve_poll_id(deps.storage, poll_id)?)
+        }
+        QueryMsg::AuditLog { start_after, limit } => query_audit_log(deps, env, start_after, limit),
+        QueryMsg::Lottery { poll_id } => {
+            to_binary(&LOTTERIES.load(deps.storage, resolve_poll_id(deps.storage, poll_id)?)?)
+        }
+        QueryMsg::RewardPool { poll_id } => to_binary(
+            &REWARD_POOLS
+                .may_load(deps.storage, resolve_poll_id(deps.storage, poll_id)?)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::Hooks {} => to_binary(&hooks_response(deps.storage, &HOOKS)?),
+        QueryMsg::Dao {} => to_binary(
+            &CONFIG
+                .load(deps.storage)?
+                .dao
+                .ok_or_else(|| StdError::not_found("dao"))?,
+        ),
+        QueryMsg::Info {} => to_binary(&InfoResponse {
+            info: get_contract_version(deps.storage)?,
+        }),
+        QueryMsg::ProposalCount {} => to_binary(&STATS.load(deps.storage)?.total_polls),
+        QueryMsg::NextProposalId {} => to_binary(&NEXT_POLL_ID.load(deps.storage)?),
+        QueryMsg::ProposalHooks {} => to_binary(&hooks_response(deps.storage, &PROPOSAL_HOOKS)?),
+        QueryMsg::VoteHooks {} => to_binary(&hooks_response(deps.storage, &VOTE_HOOKS)?),
+        QueryMsg::PollActions { poll_id } => to_binary(
+            &POLL_ACTIONS.may_load(deps.storage, resolve_poll_id(deps.storage, poll_id)?)?,
+        ),
+        QueryMsg::PendingFees {} => to_binary(&PendingFeesResponse {
+            fees: load_fees(deps.storage)?,
+            cw20_fees: load_cw20_fees(deps.storage)?,
+        }),
+    }
+}
+
+fn query_all_polls(
+    deps: Deps,
+    _env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let order = order.map_or(Order::Ascending, Order::from);
+    let (min, max) = page_bounds(start_after.as_deref(), order);
+    let polls = POLLS
+        .range(deps.storage, min, max, order) // Iterating in poll_id order
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?; // Stores it in a vector
+
+    let next = next_start_after(&polls, limit, |(poll_id, _)| poll_id.clone());
+    let total_count = POLL_COUNT.load(deps.storage)?;
+    polls_response(polls, next, Some(total_count))
+}
+
+// Bounds of the next page after start_after, which is the minimum when
+// ascending and the maximum when descending
+fn page_bounds<'a, K: PrimaryKey<'a>>(
+    start_after: Option<K>,
+    order: Order,
+) -> (Option<Bound<'a, K>>, Option<Bound<'a, K>>) {
+    let bound = start_after.map(Bound::exclusive);
+    match order {
+        Order::Ascending => (bound, None),
+        Order::Descending => (None, bound),
+    }
+}
+
+fn query_polls_by_creator(
+    deps: Deps,
+    _env: Env,
+    creator: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<Binary> {
+    let creator = deps.api.addr_validate(&creator)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let order = order.map_or(Order::Ascending, Order::from);
+    let (min, max) = page_bounds(start_after, order);
+    let polls = POLLS
+        .idx
+        .creator
+        .prefix(creator)
+        .range(deps.storage, min, max, order)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let next = next_start_after(&polls, limit, |(poll_id, _)| poll_id.clone());
+    polls_response(polls, next, None)
+}
+
+fn query_polls_by_status(
+    deps: Deps,
+    _env: Env,
+    status: PollStatus,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let order = order.map_or(Order::Ascending, Order::from);
+    let (min, max) = page_bounds(start_after, order);
+    let polls = POLLS
+        .idx
+        .status
+        .prefix(status.as_str().to_string())
+        .range(deps.storage, min, max, order)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let next = next_start_after(&polls, limit, |(poll_id, _)| poll_id.clone());
+    polls_response(polls, next, None)
+}
+
+fn query_polls_by_creator_and_status(
+    deps: Deps,
+    _env: Env,
+    creator: String,
+    status: PollStatus,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<Binary> {
+    let creator = deps.api.addr_validate(&creator)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let order = order.map_or(Order::Ascending, Order::from);
+    let (min, max) = page_bounds(start_after, order);
+    let polls = POLLS
+        .idx
+        .creator_status
+        .prefix((creator, status.as_str().to_string()))
+        .range(deps.storage, min, max, order)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let next = next_start_after(&polls, limit, |(poll_id, _)| poll_id.clone());
+    polls_response(polls, next, None)
+}
+
+fn query_polls_ending_before(
+    deps: Deps,
+    _env: Env,
+    timestamp: Timestamp,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // The empty poll_id sorts before any poll expiring at timestamp
+    let end = Bound::exclusive((timestamp.nanos(), String::new()));
+    let polls = POLLS
+        .idx
+        .expiration
+        .range(deps.storage, None, Some(end), Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    polls_response(polls, None, None)
+}
+
+fn query_polls_by_id(deps: Deps, _env: Env, poll_ids: Vec<String>) -> StdResult<Binary> {
+    if poll_ids.len() > MAX_LIMIT as usize {
+        return Err(StdError::generic_err(format!(
+            "At most {MAX_LIMIT} polls per query"
+        )));
+    }
+    // Unknown ids are skipped
+    let mut polls = vec![];
+    for poll_id in poll_ids {
+        if let Some(poll) = POLLS.may_load(deps.storage, &poll_id)? {
+            polls.push((poll_id, poll));
+        }
+    }
+    polls_response(polls, None, None)
+}
+
+fn query_recent_polls(deps: Deps, _env: Env, limit: Option<u32>) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let polls = POLLS
+        .idx
+        .created
+        .range(deps.storage, None, None, Order::Descending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    polls_response(polls, None, None)
+}
+
+fn query_polls_by_tag(
+    deps: Deps,
+    _env: Env,
+    tag: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let polls = POLLS
+        .idx
+        .tags
+        .prefix(tag)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|poll_id| {
+            let poll_id = poll_id?;
+            let poll = POLLS.load(deps.storage, &poll_id)?;
+            Ok((poll_id, poll))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let next = next_start_after(&polls, limit, |(poll_id, _)| poll_id.clone());
+    polls_response(polls, next, None)
+}
+
+fn query_polls_with_prefix(
+    deps: Deps,
+    _env: Env,
+    prefix: String,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // Slugs sharing the prefix are contiguous, starting at the prefix itself
+    let polls = SLUGS
+        .range(
+            deps.storage,
+            Some(Bound::inclusive(prefix.clone())),
+            None,
+            Order::Ascending,
+        )
+        .take_while(|slug| {
+            slug.as_ref()
+                .map_or(true, |(slug, _)| slug.starts_with(&prefix))
+        })
+        // Deleted polls keep their slug until pruned
+        .filter_map(|slug| {
+            let (_, poll_id) = match slug {
+                Ok(slug) => slug,
+                Err(err) => return Some(Err(err)),
+            };
+            POLLS
+                .may_load(deps.storage, &poll_id)
+                .transpose()
+                .map(|poll| poll.map(|poll| (poll_id, poll)))
+        })
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    polls_response(polls, None, None)
+}
+
+fn query_open_polls_not_voted(
+    deps: Deps,
+    _env: Env,
+    address: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let address = deps.api.addr_validate(&address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let polls = POLLS
+        .idx
+        .status
+        .prefix(PollStatus::Open.as_str().to_string())
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|poll| {
+            poll.as_ref().map_or(true, |(poll_id, _)| {
+                !BALLOTS.key((&address, poll_id)).has(deps.storage)
+            })
+        })
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let next = next_start_after(&polls, limit, |(poll_id, _)| poll_id.clone());
+    polls_response(polls, next, None)
+}
+
+fn query_poll(deps: Deps, _env: Env, poll_id: String) -> StdResult<Binary> {
+    let poll = POLLS.may_load(deps.storage, &poll_id)?; // Gets the poll with commented id
+    to_binary(&PollResponse { poll })
+}
+
+fn query_poll_metadata(deps: Deps, _env: Env, poll_id: String) -> StdResult<Binary> {
+    let metadata = POLLS
+        .may_load(deps.storage, &poll_id)?
+        .map(|poll| PollMetadata {
+            creator: poll.creator,
+            question: poll.question,
+            status: poll.status,
+            tags: poll.tags,
+            created_at: poll.created_at,
+            expires_at: poll.expires_at,
+        });
+    to_binary(&PollMetadataResponse { metadata })
+}
+
+fn query_poll_exists(deps: Deps, _env: Env, poll_id: String) -> StdResult<Binary> {
+    let exists = POLLS.has(deps.storage, &poll_id);
+    to_binary(&PollExistsResponse { exists })
+}
+
+// Whether the tallies of a poll are still secret, see Poll::hide_results
+fn results_hidden(poll: &Poll) -> bool {
+    poll.hide_results && matches!(poll.status, PollStatus::Open | PollStatus::Paused)
+}
+
+fn polls_response(
+    polls: Vec<(String, Poll)>,
+    next_start_after: Option<String>,
+    total_count: Option<u64>,
+) -> StdResult<Binary> {
+    to_binary(&AllPollsResponse {
+        polls,
+        next_start_after,
+        total_count,
+    })
+}
+
+// A full page may be followed by more items, starting after its last key
+fn next_start_after<T>(page: &[T], limit: usize, key: impl Fn(&T) -> String) -> Option<String> {
+    if page.len() < limit {
+        return None;
+    }
+    page.last().map(key)
+}
+
+// Loads a poll for the queries revealing its results
+fn load_public_results(storage: &dyn Storage, poll_id: &str) -> StdResult<Poll> {
+    let poll = POLLS.load(storage, poll_id)?;
+    if results_hidden(&poll) {
+        return Err(StdError::generic_err(
+            "Results are hidden until the poll is closed",
+        ));
+    }
+    Ok(poll)
+}
+
+// Ballots reveal the results too. Those of deleted polls are kept public
+fn ballot_hidden(storage: &dyn Storage, poll_id: &str) -> StdResult<bool> {
+    Ok(POLLS
+        .may_load(storage, poll_id)?
+        .is_some_and(|poll| results_hidden(&poll)))
+}
+
+fn ensure_ballots_public(storage: &dyn Storage, poll_id: &str) -> StdResult<()> {
+    if ballot_hidden(storage, poll_id)? {
+        return Err(StdError::generic_err(
+            "Results are hidden until the poll is closed",
+        ));
+    }
+    Ok(())
+}
+
+// Lazy polls that aren't closed have no tallies yet
+fn ensure_tallied(poll: &Poll) -> StdResult<()> {
+    if poll.lazy_tally && poll.status != PollStatus::Closed {
+        return Err(StdError::generic_err(
+            "Votes of this poll are only counted when it is closed",
+        ));
+    }
+    Ok(())
+}
+
+// Votes of each option of a poll, in the order of its options
+fn load_tallies(
+    storage: &dyn Storage,
+    poll_id: &str,
+    poll: &Poll,
+) -> StdResult<Vec<(String, Uint128)>> {
+    ensure_tallied(poll)?;
+    poll.options
+        .iter()
+        .map(|option| {
+            let votes = TALLIES
+                .may_load(storage, (poll_id.to_string(), option.label.clone()))?
+                .unwrap_or_default();
+            Ok((option.label.clone(), votes))
+        })
+        .collect()
+}
+
+// Leading option of a poll and whether several options share the lead. There
+// is no winner without votes, on a tie or if the poll was vetoed or removed
+fn poll_winner(poll: &Poll, tallies: &[(String, Uint128)]) -> (Option<String>, bool) {
+    let top = tallies
+        .iter()
+        .map(|(_, votes)| *votes)
+        .max()
+        .unwrap_or_default();
+    let leaders: Vec<_> = tallies
+        .iter()
+        .filter(|(_, votes)| !top.is_zero() && *votes == top)
+        .collect();
+    let tie = leaders.len() > 1;
+    let counts = !matches!(poll.status, PollStatus::Vetoed | PollStatus::Removed);
+    let winner = match leaders.as_slice() {
+        [(option, _)] if counts => Some(option.clone()),
+        _ if tie && counts => poll.tie_winner.clone(),
+        _ => None,
+    };
+    (winner, tie)
+}
+
+fn query_winner(deps: Deps, _env: Env, poll_id: String) -> StdResult<Binary> {
+    let poll = load_public_results(deps.storage, &poll_id)?;
+    let tallies = load_tallies(deps.storage, &poll_id, &poll)?;
+    let (winner, _) = poll_winner(&poll, &tallies);
+    to_binary(&WinnerResponse { winner })
+}
+
+fn query_poll_results(deps: Deps, _env: Env, poll_id: String) -> StdResult<Binary> {
+    let poll = load_public_results(deps.storage, &poll_id)?;
+    to_binary(&poll_results(deps.storage, &poll_id, &poll)?)
+}
+
+// Creators, managers and the owner can see hidden results with a signed permit
+fn query_poll_results_with_permit(
+    deps: Deps,
+    env: Env,
+    poll_id: PollId,
+    permit: ResultsPermit,
+) -> StdResult<Binary> {
+    let signer = pubkey_to_address(&env.contract.address, &permit.pubkey)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    // Signed with the poll id as sent, number or slug
+    let payload = to_vec(&ResultsPermitPayload {
+        contract: env.contract.address.to_string(),
+        poll_id: poll_id.to_string(),
+    })?;
+    let poll_id = resolve_poll_id(deps.storage, poll_id)?;
+    let poll = POLLS.load(deps.storage, &poll_id)?;
+    let message_hash = Sha256::digest(adr036_sign_doc(&signer, &payload));
+    let verified = deps
+        .api
+        .secp256k1_verify(&message_hash, &permit.signature, &permit.pubkey)
+        .unwrap_or(false);
+    if !verified {
+        return Err(StdError::generic_err(
+            ContractError::InvalidSignature {}.to_string(),
+        ));
+    }
+    if signer != poll.creator && !has_role(deps.storage, &signer, Role::Manager)? {
+        return Err(StdError::generic_err(
+            ContractError::Unauthorized {
+                sender: signer.to_string(),
+            }
+            .to_string(),
+        ));
+    }
+    to_binary(&poll_results(deps.storage, &poll_id, &poll)?)
+}
+
+fn poll_results(
+    storage: &dyn Storage,
+    poll_id: &str,
+    poll: &Poll,
+) -> StdResult<PollResultsResponse> {
+    let tallies = load_tallies(storage, poll_id, poll)?;
+    let total_votes = tallies
+        .iter()
+        .try_fold(Uint128::zero(), |total, (_, votes)| {
+            total.checked_add(*votes)
+        })?;
+    let options = poll
+        .options
+        .iter()
+        .zip(&tallies)
+        .map(|(poll_option, (option, votes))| {
+            let percentage = if total_votes.is_zero() {
+                Decimal::zero()
+            } else {
+                Decimal::from_ratio(votes.checked_mul(Uint128::new(100))?, total_votes)
+            };
+            Ok(OptionResult {
+                id: poll_option.id,
+                option: option.clone(),
+                votes: *votes,
+                percentage,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let (winner, tie) = poll_winner(poll, &tallies);
+
+    Ok(PollResultsResponse {
+        total_votes,
+        options,
+        winner,
+        tie,
+        tie_break: poll.tie_break,
+        is_final: !matches!(poll.status, PollStatus::Open | PollStatus::Paused),
+    })
+}
+
+fn query_option_tally(deps: Deps, _env: Env, poll_id: String, option: String) -> StdResult<Binary> {
+    let poll = load_public_results(deps.storage, &poll_id)?;
+    ensure_tallied(&poll)?;
+    if !poll.has_option(&option) {
+        return Err(StdError::not_found("poll option"));
+    }
+    let votes = TALLIES
+        .may_load(deps.storage, (poll_id, option))?
+        .unwrap_or_default();
+    to_binary(&OptionTallyResponse { votes })
+}
+
+fn query_poll_voters(
+    deps: Deps,
+    _env: Env,
+    poll_id: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let poll = load_public_results(deps.storage, &poll_id)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?
+        .map(Bound::exclusive);
+    // VOTERS is ranged by poll, the ballots themselves are keyed by voter
+    let voters = VOTERS
+        .prefix(poll_id.clone())
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|voter| {
+            let voter = voter?;
+            let ballot = BALLOTS.load(deps.storage, (&voter, &poll_id))?;
+            Ok((voter, ballot.option, ballot.weight))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    to_binary(&PollVotersResponse {
+        next_start_after: next_start_after(&voters, limit, |(voter, _, _)| voter.to_string()),
+        total_count: Some(poll.voter_count),
+        voters,
+    })
+}
+
+fn query_votes_by_address(
+    deps: Deps,
+    _env: Env,
+    address: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let address = deps.api.addr_validate(&address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let page = BALLOTS
+        .prefix(&address)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let total_count = PARTICIPATION
+        .may_load(deps.storage, address)?
+        .unwrap_or_default();
+    // Ballots of polls hiding their results are left out of the page
+    let mut votes = vec![];
+    for (poll_id, ballot) in &page {
+        if !ballot_hidden(deps.storage, poll_id)? {
+            votes.push((poll_id.clone(), ballot.clone()));
+        }
+    }
+    to_binary(&VotesByAddressResponse {
+        next_start_after: next_start_after(&page, limit, |(poll_id, _)| poll_id.clone()),
+        total_count: Some(total_count),
+        votes,
+    })
+}
+
+fn query_voters_by_option(
+    deps: Deps,
+    _env: Env,
+    poll_id: String,
+    option: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    load_public_results(deps.storage, &poll_id)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?
+        .map(Bound::exclusive);
+    let voters = OPTION_VOTERS
+        .prefix((poll_id, option))
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    to_binary(&VotersByOptionResponse {
+        next_start_after: next_start_after(&voters, limit, Addr::to_string),
+        total_count: None,
+        voters,
+    })
+}
+
+fn query_participation(deps: Deps, _env: Env, address: String) -> StdResult<Binary> {
+    let address = deps.api.addr_validate(&address)?;
+    let polls = PARTICIPATION
+        .may_load(deps.storage, address)?
+        .unwrap_or_default();
+    to_binary(&ParticipationResponse { polls })
+}
+
+fn query_top_voters(deps: Deps, _env: Env, limit: Option<u32>) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let voters = PARTICIPATION_RANKING
+        .keys(deps.storage, None, None, Order::Descending)
+        .take(limit)
+        .map(|key| key.map(|(polls, voter)| (voter, polls)))
+        .collect::<StdResult<Vec<_>>>()?;
+    to_binary(&TopVotersResponse { voters })
+}
+
+fn query_tally_at_height(deps: Deps, _env: Env, poll_id: String, height: u64) -> StdResult<Binary> {
+    let poll = load_public_results(deps.storage, &poll_id)?;
+    // Lazy polls have their votes counted at the closing height
+    ensure_tallied(&poll)?;
+    // As it was at the beginning of the block, before its votes. Options are
+    // all saved at creation, so the poll didn't exist yet if one is missing
+    let options = poll
+        .options
+        .into_iter()
+        .map(|option| {
+            let votes = TALLIES.may_load_at_height(
+                deps.storage,
+                (poll_id.clone(), option.label.clone()),
+                height,
+            )?;
+            Ok(votes.map(|votes| (option.label, votes)))
+        })
+        .collect::<StdResult<Option<Vec<_>>>>()?;
+    to_binary(&TallyAtHeightResponse { options })
+}
+
+fn query_vote(deps: Deps, _env: Env, address: String, poll_id: String) -> StdResult<Binary> {
+    ensure_ballots_public(deps.storage, &poll_id)?;
+    let validated_address = deps.api.addr_validate(&address)?; // Address
+    let vote = BALLOTS.may_load(deps.storage, (&validated_address, &poll_id))?; // vote
+
+    to_binary(&VoteResponse { vote }) // Return vote
+}
+
+fn query_vote_at_height(
+    deps: Deps,
+    _env: Env,
+    address: String,
+    poll_id: String,
+    height: u64,
+) -> StdResult<Binary> {
+    ensure_ballots_public(deps.storage, &poll_id)?;
+    let address = deps.api.addr_validate(&address)?;
+    // Not found for heights without a checkpoint
+    let vote = BALLOTS.may_load_at_height(deps.storage, (&address, &poll_id), height)?;
+    to_binary(&VoteResponse { vote })
+}
+
+// cw3 queries, with the numerically numbered polls as the proposals
+pub fn query_cw3(deps: Deps, _env: Env, msg: Cw3QueryMsg) -> StdResult<Binary> {
+    match msg {
+        // Zero percentage of no fixed voter set, the leading option wins
+        Cw3QueryMsg::Threshold {} => to_binary(&Cw3ThresholdResponse::AbsolutePercentage {
+            percentage: Decimal::zero(),
+            total_weight: 0,
+        }),
+        Cw3QueryMsg::Proposal { proposal_id } => {
+            let poll = POLLS.load(deps.storage, &proposal_id.to_string())?;
+            to_binary(&cw3_proposal(deps.storage, proposal_id, poll)?)
+        }
+        Cw3QueryMsg::ListProposals { start_after, limit } => {
+            query_cw3_proposals(deps, start_after, limit)
+        }
+        Cw3QueryMsg::Vote { proposal_id, voter } => {
+            let voter = deps.api.addr_validate(&voter)?;
+            let poll_id = proposal_id.to_string();
+            let poll = load_public_results(deps.storage, &poll_id)?;
+            let yes = cw3_yes_option(deps.storage, &poll_id, &poll)?;
+            let vote = BALLOTS
+                .may_load(deps.storage, (&voter, &poll_id))?
+                .map(|ballot| cw3_vote_info(proposal_id, &voter, &ballot, &yes));
+            to_binary(&Cw3VoteResponse { vote })
+        }
+        Cw3QueryMsg::ListVotes {
+            proposal_id,
+            start_after,
+            limit,
+        } => query_cw3_votes(deps, proposal_id, start_after, limit),
+    }
+}
+
+fn cw3_proposal(
+    storage: &dyn Storage,
+    proposal_id: u64,
+    poll: Poll,
+) -> StdResult<Cw3ProposalResponse> {
+    let poll_id = proposal_id.to_string();
+    let status = cw3_status(storage, &poll_id, &poll)?;
+    let actions = POLL_ACTIONS.may_load(storage, poll_id)?;
+    let description = poll
+        .options
+        .iter()
+        .map(|option| option.label.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let threshold = actions
+        .as_ref()
+        .and_then(|actions| actions.threshold)
+        .unwrap_or_default();
+    Ok(Cw3ProposalResponse {
+        id: proposal_id,
+        title: poll.question,
+        description,
+        msgs: actions.map(|actions| actions.msgs).unwrap_or_default(),
+        status,
+        expires: poll
+            .expires_at
+            .map_or(Expiration::Never {}, Expiration::AtTime),
+        threshold: Cw3ThresholdResponse::AbsolutePercentage {
+            percentage: threshold,
+            total_weight: poll.voter_count,
+        },
+        proposer: poll.creator,
+        deposit: None,
+    })
+}
+
+fn cw3_status(storage: &dyn Storage, poll_id: &str, poll: &Poll) -> StdResult<Cw3Status> {
+    Ok(match poll.status {
+        PollStatus::Open | PollStatus::Paused => Cw3Status::Open,
+        PollStatus::Vetoed | PollStatus::Removed => Cw3Status::Rejected,
+        PollStatus::Closed => {
+            let tallies = load_tallies(storage, poll_id, poll)?;
+            let (winner, _) = poll_winner(poll, &tallies);
+            match POLL_ACTIONS.may_load(storage, poll_id.to_string())? {
+                // The messages of a passed poll are sent when it's closed
+                Some(actions) if actions_passed(&actions, winner.as_deref(), &tallies) => {
+                    Cw3Status::Executed
+                }
+                None if winner.is_some() => Cw3Status::Passed,
+                _ => Cw3Status::Rejected,
+            }
+        }
+    })
+}
+
+// Auto numbered polls only, custom ids past NEXT_POLL_ID are left out of the pages
+fn query_cw3_proposals(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let next = NEXT_POLL_ID.load(deps.storage)?;
+    let first = start_after.map_or(1, |id| id.saturating_add(1));
+    let mut proposals = vec![];
+    for proposal_id in first..next {
+        if proposals.len() == limit {
+            break;
+        }
+        if let Some(poll) = POLLS.may_load(deps.storage, &proposal_id.to_string())? {
+            proposals.push(cw3_proposal(deps.storage, proposal_id, poll)?);
+        }
+    }
+    to_binary(&Cw3ProposalListResponse { proposals })
+}
+
+fn query_cw3_votes(
+    deps: Deps,
+    proposal_id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let poll_id = proposal_id.to_string();
+    let poll = load_public_results(deps.storage, &poll_id)?;
+    let yes = cw3_yes_option(deps.storage, &poll_id, &poll)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?
+        .map(Bound::exclusive);
+    let votes = VOTERS
+        .prefix(poll_id.clone())
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|voter| {
+            let voter = voter?;
+            let ballot = BALLOTS.load(deps.storage, (&voter, &poll_id))?;
+            Ok(cw3_vote_info(proposal_id, &voter, &ballot, &yes))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    to_binary(&Cw3VoteListResponse { votes })
+}
+
+// Option counted as a yes, the one of the actions or else the first one
+fn cw3_yes_option(storage: &dyn Storage, poll_id: &str, poll: &Poll) -> StdResult<String> {
+    Ok(match POLL_ACTIONS.may_load(storage, poll_id.to_string())? {
+        Some(actions) => actions.option,
+        None => poll
+            .options
+            .first()
+            .map(|option| option.label.clone())
+            .unwrap_or_default(),
+    })
+}
+
+// Options named like a cw3 vote are that vote, the others yes or no
+fn cw3_vote_info(proposal_id: u64, voter: &Addr, ballot: &Ballot, yes: &str) -> Cw3VoteInfo {
+    let vote = match ballot.option.to_lowercase().as_str() {
+        "yes" => Cw3Vote::Yes,
+        "no" => Cw3Vote::No,
+        "abstain" => Cw3Vote::Abstain,
+        "veto" | "no with veto" => Cw3Vote::Veto,
+        _ if ballot.option == yes => Cw3Vote::Yes,
+        _ => Cw3Vote::No,
+    };
+    Cw3VoteInfo {
+        proposal_id,
+        voter: voter.to_string(),
+        vote,
+        weight: ballot.weight.u128().try_into().unwrap_or(u64::MAX),
+    }
+}
+
+fn query_audit_log(
+    deps: Deps,
+    _env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let entries = AUDIT_LOG
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let next_start_after = if entries.len() == limit {
+        entries.last().map(|(seq, _)| *seq)
+    } else {
+        None
+    };
+    to_binary(&AuditLogResponse {
+        entries,
+        next_start_after,
+    })
+}
+
+fn query_removal(deps: Deps, _env: Env, poll_id: String) -> StdResult<Binary> {
+    let removal = REMOVALS.may_load(deps.storage, poll_id)?;
+    to_binary(&RemovalResponse { removal })
+}
+
+fn query_contract_info(deps: Deps, _env: Env) -> StdResult<Binary> {
+    let config = CONFIG.load(deps.storage)?;
+    let version = get_contract_version(deps.storage)?;
+
+    // Optional behaviours switched on in this deployment
+    let features = [
+        ("public_creation", config.allow_public_creation),
+        ("paused", config.paused),
+        ("vote_cooldown", config.vote_cooldown.is_some()),
+        ("creation_fee", config.creation_fee.is_some()),
+        ("cw20_creation_fee", config.cw20_creation_fee.is_some()),
+        ("creation_deposit", config.creation_deposit.is_some()),
+        ("flag_threshold", config.flag_threshold.is_some()),
+        (
+            "max_open_polls_per_creator",
+            config.max_open_polls_per_creator.is_some(),
+        ),
+        ("debug_events", config.debug_events),
+        ("voted_nft", config.voted_nft.is_some()),
+        ("participation_token", config.participation_denom.is_some()),
+        ("randomness", config.nois_proxy.is_some()),
+        ("scheduled_finalize", config.croncat_tasks.is_some()),
+        ("dao_module", config.dao.is_some()),
+    ]
+    .into_iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(feature, _)| feature.to_string())
+    .collect();
+
+    to_binary(&ContractInfoResponse {
+        contract_name: version.contract,
+        contract_version: version.version,
+        admin: cw_ownable::get_ownership(deps.storage)?.owner,
+        features,
+    })
+}
+
+fn query_config(deps: Deps, _env: Env) -> StdResult<Binary> {
+    let config = CONFIG.load(deps.storage)?;
+    let owner = cw_ownable::get_ownership(deps.storage)?.owner;
+    let version = get_contract_version(deps.storage)?;
+
+    to_binary(&ConfigResponse {
+        config,
+        owner,
+        contract_name: version.contract,
+        contract_version: version.version,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::contract::{
+        adr036_sign_doc, entry, execute, instantiate, migrate, pubkey_to_address, query, reply,
+        sudo,
+    }; // Adding execute
+    use crate::events::{
+        evt_ballot_cast, evt_ballot_changed, evt_poll_closed, evt_poll_created, evt_vote,
+    };
+    use crate::msg::{
+        AllPollsResponse, AuditLogResponse, ConfigResponse, ConfigUpdate, ContractInfoResponse,
+        CreatePollResponse, CroncatAction, CroncatBoundary, CroncatExecuteMsg, CroncatInterval,
+        CroncatTaskRequest, Cw3ProposalListResponse, Cw3ProposalResponse, Cw3Status,
+        Cw3ThresholdResponse, Cw3Vote, Cw3VoteListResponse, Cw3VoteResponse, Cw721ExecuteMsg,
+        DaoHookExecuteMsg, DaoQueryMsg, ExecuteMsg, HooksResponse, InfoResponse, InstantiateMsg,
+        MigrateMsg, NoisCallback, NoisProxyExecuteMsg, OptionResult, OptionTallyResponse, OrderBy,
+        ParticipationResponse, PendingFeesResponse, PollExistsResponse, PollHookExecuteMsg,
+        PollHookMsg, PollMetadataResponse, PollResponse, PollResultsResponse, PollVotersResponse,
+        ProposalHookMsg, QueryEntryMsg, QueryMsg, ReceiveMsg, RemovalResponse, ResultsPermit,
+        ResultsPermitPayload, SignedVotePayload, SudoMsg, TallyAtHeightResponse, TopVotersResponse,
+        VoteHookMsg, VoteResponse, VotersByOptionResponse, VotesByAddressResponse,
+        VotingPowerAtHeightResponse, WinnerResponse,
+    }; // Adding ExecuteMsg
+    use crate::state::{
+        AuditEntry, Lottery, Poll, PollActions, PollOption, PollStatus, RewardPool, Role, Stats,
+        TieBreak, CONFIG, FEES, POLLS, STORED_BALLOTS, TALLIES,
+    };
+    use crate::ContractError;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{
+        attr, coin, from_binary, from_slice, to_binary, to_vec, Addr, BankMsg, Binary, Coin,
+        ContractResult, CosmosMsg, Decimal, Event, HexBinary, Order, OwnedDeps, Record, Reply,
+        StdError, Storage, SubMsg, SubMsgResponse, SubMsgResult, SystemResult, Uint128, WasmMsg,
+        WasmQuery,
+    }; // constructs an attribute // mock functions
+    use cw2::set_contract_version;
+    use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20ReceiveMsg};
+    use cw_ownable::{Action, Ownership, OwnershipError};
+    use cw_utils::{Expiration, PaymentError};
+    use k256::ecdsa::signature::Signer;
+    use k256::ecdsa::{Signature, SigningKey};
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+    use std::cell::Cell;
+    use std::marker::PhantomData;
+
+    // Fake addresses
+    pub const ADDR1: &str = "addr1";
+    pub const ADDR2: &str = "addr2";
+
+    // Counts the storage accesses of the messages it runs
+    #[derive(Default)]
+    struct CountingStorage {
+        storage: MockStorage,
+        reads: Cell<u32>,
+        writes: u32,
+    }
+
+    impl CountingStorage {
+        // (reads, writes) since the last call
+        fn take_counts(&mut self) -> (u32, u32) {
+            (self.reads.take(), std::mem::take(&mut self.writes))
+        }
+    }
+
+    impl Storage for CountingStorage {
+        fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+            self.reads.set(self.reads.get() + 1);
+            self.storage.get(key)
+        }
+
+        fn range<'a>(
+            &'a self,
+            start: Option<&[u8]>,
+            end: Option<&[u8]>,
+            order: Order,
+        ) -> Box<dyn Iterator<Item = Record> + 'a> {
+            self.reads.set(self.reads.get() + 1);
+            self.storage.range(start, end, order)
+        }
+
+        fn set(&mut self, key: &[u8], value: &[u8]) {
+            self.writes += 1;
+            self.storage.set(key, value)
+        }
+
+        fn remove(&mut self, key: &[u8]) {
+            self.writes += 1;
+            self.storage.remove(key)
+        }
+    }
+
+    #[test]
+    fn test_instantiate() {
+        let mut deps = mock_dependencies();
+        // Mock the contract environment (like a real chain)
+        let env = mock_env();
+        // Mock the message info, ADDR1 will be the sender, the empty vec means we sent no funds.
+        let info = mock_info(ADDR1, &[]);
+
+        // Create a message where the sender will be an admin
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        // Call instantiate, unwrap to assert success
+        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "instantiate"), attr("admin", ADDR1)]
+        )
+    }
+
+    #[test]
+    fn test_instantiate_with_admin() {
+        // Copy paste of test_instantiate but changes None to Some(ADDR2.to_string())
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR2, &[]);
+
+        let msg = InstantiateMsg {
+            admin: Some(ADDR2.to_string()),
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "instantiate"), attr("admin", ADDR2)]
+        )
+    }
+
+    // Start of new tests
+
+    // Poll valid tests
+    #[test]
+    fn test_execute_create_poll_valid() {
+        // The Mock values like in instantiate
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // New execute msg
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("Web3Builders".parse().unwrap()),
+            question: "Will I finish this on time?".to_string(),
+            options: vec![
+                "Yes".to_string(),
+                "No".to_string(),
+                "The world will end before that".to_string(),
+            ],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+
+        // Unwrap to assert success
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "create_poll"),
+                attr("poll_id", "1"),
+                attr("creator", ADDR1),
+                attr("options", "3"),
+                attr("expires_at", "none"),
+                attr("contract_version", env!("CARGO_PKG_VERSION")),
+            ]
+        );
+        assert_eq!(
+            res.events,
+            vec![Event::new("poll_created").add_attributes(vec![
+                ("poll_id", "1"),
+                ("creator", ADDR1),
+                ("options", "3"),
+                ("expires_at", "none"),
+            ])]
+        );
+
+        // The slug points to the poll, it can't be reused nor be a number
+        let msg_poll = QueryMsg::Poll {
+            poll_id: "Web3Builders".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg_poll).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        assert_eq!(poll.slug, Some("Web3Builders".to_string()));
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::PollIdTaken { .. }));
+        let mut numeric = msg;
+        if let ExecuteMsg::CreatePoll { slug, .. } = &mut numeric {
+            *slug = Some("7".parse().unwrap());
+        }
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), numeric).unwrap_err();
+        assert!(matches!(err, ContractError::NumericSlug { .. }));
+
+        // Without a slug the poll only has its number, returned as well
+        for expected in ["2", "3"] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: None,
+                question: "Yes or no?".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+            let data: CreatePollResponse = from_binary(&res.data.unwrap()).unwrap();
+            assert_eq!(data.poll_id, expected);
+            assert_eq!(data.poll.created_height, env.block.height);
+            assert!(res.attributes.contains(&attr("poll_id", expected)));
+        }
+    }
+
+    //Poll invalid tests
+    #[test]
+    fn test_execute_create_poll_invalid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "How many numbers fit in this poll?".to_string(),
+            options: vec![
+                "1".to_string(),
+                "2".to_string(),
+                "3".to_string(),
+                "4".to_string(),
+                "5".to_string(),
+                "6".to_string(),
+            ],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+
+        let _err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Yes or yes?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string(), "Yes".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::DuplicateOption { option } if option == "Yes"));
+
+        // Lengths are limited by the config, 280 and 64 bytes by default
+        let create_msg = |question: String, option: String| ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question,
+            options: vec!["Yes".to_string(), option],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let msg = create_msg("?".repeat(281), "No".to_string());
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::QuestionTooLong { max: 280 }));
+        let msg = create_msg("Yes or no?".to_string(), "N".repeat(65));
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::OptionTooLong { max: 64, .. }));
+        let msg = create_msg("?".repeat(280), "N".repeat(64));
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // Malformed ids are rejected when the message is parsed
+        for poll_id in ["", "has space", &"x".repeat(65)] {
+            let msg = format!(r#"{{"close_poll":{{"poll_id":"{poll_id}"}}}}"#);
+            assert!(from_slice::<ExecuteMsg>(msg.as_bytes()).is_err());
+        }
+        let msg = br#"{"close_poll":{"poll_id":"community/2024-1"}}"#;
+        assert!(from_slice::<ExecuteMsg>(msg).is_ok());
+    }
+
+    // Vote valid
+    #[test]
+    fn test_execute_vote_valid() {
+        // Envorinment
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Poll created
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("000".parse().unwrap()),
+            question: "Choose an option".to_string(),
+            options: vec!["1".to_string(), "2".to_string(), "3".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Vote executed
+        let msg = ExecuteMsg::Vote {
+            poll_id: "000".parse().unwrap(),
+            vote: "1".to_string(),
+            on_behalf_of: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "vote"),
+                attr("poll_id", "1"),
+                attr("voter", ADDR1),
+                attr("option", "1"),
+                attr("contract_version", env!("CARGO_PKG_VERSION")),
+            ]
+        );
+        assert_eq!(res.events[1].ty, "ballot_cast");
+
+        // Update vote
+        let msg = ExecuteMsg::Vote {
+            poll_id: "000".parse().unwrap(),
+            vote: "2".to_string(),
+            on_behalf_of: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        assert!(res.attributes.contains(&attr("previous_option", "1")));
+        assert_eq!(res.events[0].ty, "poll_voted");
+        assert!(res.events[0]
+            .attributes
+            .contains(&attr("previous_option", "1")));
+        assert_eq!(
+            res.events[1],
+            Event::new("ballot_changed")
+                .add_attribute("old_option", "1")
+                .add_attribute("new_option", "2")
+                .add_attribute("poll_id", "1")
+                .add_attribute("voter", ADDR1)
+        );
+
+        // Same vote again, neither cast nor changed
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(res.events.len(), 1);
+
+        // Diagnostic attributes once debug events are on
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate {
+            min_options: None,
+            max_options: None,
+            allow_public_creation: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            max_question_length: None,
+            max_option_length: None,
+            debug_events: Some(true),
+            voted_nft: None,
+            participation_denom: None,
+            nois_proxy: None,
+            croncat_tasks: None,
+            dao: None,
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::Vote {
+            poll_id: "000".parse().unwrap(),
+            vote: "3".to_string(),
+            on_behalf_of: None,
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert!(res.attributes.contains(&attr("debug_tally_before", "0")));
+        assert!(res.attributes.contains(&attr("debug_tally_after", "1")));
+        assert!(res.attributes.contains(&attr("debug_voter_count", "1")));
+    }
+
+    // Storage (reads, writes) of a vote, the same whatever the size of the poll.
+    // Raising them needs a reason, gas is mostly spent there. After a poll close
+    // the first change of each ballot also writes its BALLOTS changelog. New and
+    // changed ballots read the HOOKS and VOTE_HOOKS to notify, and write the vote
+    // count of the poll. The poll id is looked up in SLUGS first
+    const FIRST_VOTE_BUDGET: (u32, u32) = (13, 9);
+    const CHANGED_VOTE_BUDGET: (u32, u32) = (13, 6);
+    const SAME_VOTE_BUDGET: (u32, u32) = (6, 0);
+
+    #[test]
+    fn test_execute_vote_storage_budget() {
+        let mut deps = OwnedDeps {
+            storage: CountingStorage::default(),
+            api: MockApi::default(),
+            querier: MockQuerier::default(),
+            custom_query_type: PhantomData,
+        };
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: Some(50),
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Pick a number".to_string(),
+            options: (0..50).map(|option| option.to_string()).collect(),
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        for voter in 0..100 {
+            let msg = ExecuteMsg::Vote {
+                poll_id: "001".parse().unwrap(),
+                vote: (voter % 50).to_string(),
+                on_behalf_of: None,
+            };
+            let info = mock_info(&format!("voter{voter}"), &[]);
+            let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
+
+        let vote = |option: &str| ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: option.to_string(),
+            on_behalf_of: None,
+        };
+        deps.storage.take_counts();
+        // First ballot
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), vote("7")).unwrap();
+        assert_eq!(deps.storage.take_counts(), FIRST_VOTE_BUDGET);
+        // Changed ballot, only the vote count of the poll moves
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), vote("8")).unwrap();
+        assert_eq!(deps.storage.take_counts(), CHANGED_VOTE_BUDGET);
+        // Same ballot again, nothing is written
+        let _res = execute(deps.as_mut(), env, mock_info(ADDR2, &[]), vote("8")).unwrap();
+        assert_eq!(deps.storage.take_counts(), SAME_VOTE_BUDGET);
+    }
+
+    // Vote invalid section
+    #[test]
+    fn test_execute_vote_invalid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Vote created, poll doesn't exist.
+        let msg = ExecuteMsg::Vote {
+            poll_id: "000".parse().unwrap(),
+            vote: "Okonomiyaki".to_string(),
+            on_behalf_of: None,
+        };
+        // Unwrap to assert error
+        let _err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+
+        // Poll created
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("000".parse().unwrap()),
+            question: "Favorite Japanese food".to_string(),
+            options: vec![
+                "Onigiri".to_string(),
+                "Okonomiyaki".to_string(),
+                "Ozoni".to_string(),
+            ],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Poll created, Vote done, option
+        let msg = ExecuteMsg::Vote {
+            poll_id: "000".parse().unwrap(),
+            vote: "Pizza".to_string(),
+            on_behalf_of: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err.to_string(), "[E021] Poll 1 has no option Pizza");
+        // The rejected vote left no ballot behind
+        let msg = QueryMsg::Vote {
+            poll_id: "000".parse().unwrap(),
+            address: info.sender.to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: VoteResponse = from_binary(&bin).unwrap();
+        assert!(res.vote.is_none());
+
+        // A tally out of sync with the ballots is an error, not a panic
+        let msg = ExecuteMsg::Vote {
+            poll_id: "000".parse().unwrap(),
+            vote: "Onigiri".to_string(),
+            on_behalf_of: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        TALLIES
+            .save(
+                deps.as_mut().storage,
+                ("1".to_string(), "Onigiri".to_string()),
+                &Uint128::zero(),
+                env.block.height,
+            )
+            .unwrap();
+        let msg = ExecuteMsg::Vote {
+            poll_id: "000".parse().unwrap(),
+            vote: "Ozoni".to_string(),
+            on_behalf_of: None,
+        };
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(
+            matches!(err, ContractError::TallyCorruption { option, .. } if option == "Onigiri")
+        );
+    }
+
+    #[test]
+    fn test_execute_close_poll() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // ADDR2 creates the poll
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+        let msg = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Soon".to_string(),
+            on_behalf_of: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        // Random address cannot close it
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr3", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotPollCreator { .. }));
+        assert_eq!(
+            err.to_string(),
+            "[E005] Address addr3 is not the creator of poll 1"
+        );
+
+        // Admin can
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(res.events[0].ty, "poll_closed");
+        assert!(res.events[0].attributes.contains(&attr("closed_by", ADDR1)));
+        assert!(res.events[0].attributes.contains(&attr("winner", "Soon")));
+        assert!(res.events[0]
+            .attributes
+            .contains(&attr("winner_votes", "1")));
+        assert!(res.events[0].attributes.contains(&attr("turnout", "1")));
+
+        // No more votes after closing
+        let msg = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Now".to_string(),
+            on_behalf_of: None,
+        };
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::PollClosed { .. }));
+    }
+
+    #[test]
+    fn test_query_vote_at_height() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Yes or no?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Yes".to_string(),
+            on_behalf_of: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let closed_height = env.block.height;
+
+        // The ballots are deleted later on
+        env.block.height += 10;
+        let msg = ExecuteMsg::EmergencyRemove {
+            poll_ids: vec!["001".parse().unwrap()],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let msg = ExecuteMsg::PruneBallots {
+            poll_id: "001".parse().unwrap(),
+            limit: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+        let msg = QueryMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            address: ADDR2.to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        assert_eq!(from_binary::<VoteResponse>(&bin).unwrap().vote, None);
+
+        // They remain as they were once the poll closed
+        let vote_at = |height| QueryMsg::VoteAtHeight {
+            poll_id: "001".parse().unwrap(),
+            address: ADDR2.to_string(),
+            height,
+        };
+        let bin = query(deps.as_ref(), env.clone(), vote_at(closed_height + 1)).unwrap();
+        let res: VoteResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.vote.unwrap().option, "Yes");
+        // Other heights have no checkpoint
+        let _err = query(deps.as_ref(), env, vote_at(closed_height)).unwrap_err();
+    }
+
+    #[test]
+    fn test_execute_close_poll_lazy_tally() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: Some(true),
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for (voter, vote) in [
+            (ADDR1, "Now"),
+            (ADDR2, "Soon"),
+            ("addr3", "Now"),
+            (ADDR2, "Never"),
+        ] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: "001".parse().unwrap(),
+                vote: vote.to_string(),
+                on_behalf_of: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        // Nothing is counted while the poll is open
+        let results = QueryMsg::PollResults {
+            poll_id: "001".parse().unwrap(),
+        };
+        let _err = query(deps.as_ref(), env.clone(), results.clone()).unwrap_err();
+        let msg = QueryMsg::OptionTally {
+            poll_id: "001".parse().unwrap(),
+            option: "Now".to_string(),
+        };
+        let _err = query(deps.as_ref(), env.clone(), msg).unwrap_err();
+
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let bin = query(deps.as_ref(), env, results).unwrap();
+        let res: PollResultsResponse = from_binary(&bin).unwrap();
+        let votes: Vec<_> = res
+            .options
+            .iter()
+            .map(|option| option.votes.u128())
+            .collect();
+        assert_eq!(votes, vec![2, 0, 1]);
+        assert_eq!(res.winner, Some("Now".to_string()));
+    }
+
+    #[test]
+    fn test_execute_veto_poll() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // ADDR2 is the veto of the poll
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: Some(ADDR2.to_string()),
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Now".to_string(),
+            on_behalf_of: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Only the veto or a manager can veto
+        let msg = ExecuteMsg::VetoPoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr3", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes[..3],
+            vec![
+                attr("action", "veto_poll"),
+                attr("poll_id", "1"),
+                attr("sender", ADDR2),
+            ]
+        );
+        assert_eq!(res.events[0].ty, "poll_closed");
+        assert!(res.events[0].attributes.contains(&attr("winner", "none")));
+
+        // Vetoed regardless of the votes
+        let msg_query = QueryMsg::Poll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg_query).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.poll.unwrap().status, PollStatus::Vetoed);
+
+        // Already finalized
+        let err = execute(deps.as_mut(), env, mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::PollClosed { .. }));
+    }
+
+    #[test]
+    fn test_execute_claim_deposit() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: Some(coin(100, "ujuno")),
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Voting without the deposit fails
+        let msg = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Now".to_string(),
+            on_behalf_of: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::DepositRequired { .. }));
+
+        // With the deposit it works, and changing the vote doesn't ask for another one
+        let voter = mock_info(ADDR2, &[coin(100, "ujuno")]);
+        let _res = execute(deps.as_mut(), env.clone(), voter, msg).unwrap();
+        let msg = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Soon".to_string(),
+            on_behalf_of: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        // No claim while the poll is open
+        let msg = ExecuteMsg::ClaimDeposit {
+            poll_id: "001".parse().unwrap(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::PollOpen { .. }));
+
+        let msg_close = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg_close).unwrap();
+
+        // Refund after closing, only once
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: ADDR2.to_string(),
+                amount: vec![coin(100, "ujuno")],
+            })
+        );
+        assert_eq!(
+            res.attributes[..4],
+            vec![
+                attr("action", "claim_deposit"),
+                attr("poll_id", "1"),
+                attr("voter", ADDR2),
+                attr("deposit", "100ujuno"),
+            ]
+        );
+        let err = execute(deps.as_mut(), env, mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoDeposit { .. }));
+    }
+
+    #[test]
+    fn test_execute_vote_cooldown() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // One vote action per minute
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: Some(60),
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Now".to_string(),
+            on_behalf_of: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Flipping the vote right away is throttled
+        let msg = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Never".to_string(),
+            on_behalf_of: None,
+        };
+        env.block.time = env.block.time.plus_seconds(30);
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::VoteCooldown { .. }));
+
+        // Other addresses are not affected
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap();
+
+        // Allowed once the cooldown is over
+        env.block.time = env.block.time.plus_seconds(30);
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    }
+
+    #[test]
+    fn test_execute_vote_on_behalf_of() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // ADDR2 is not authorized yet
+        let msg = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Soon".to_string(),
+            on_behalf_of: Some(ADDR1.to_string()),
+        };
+        let proxy = mock_info(ADDR2, &[]);
+        let err = execute(deps.as_mut(), env.clone(), proxy.clone(), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+
+        // ADDR1 authorizes ADDR2
+        let msg_proxy = ExecuteMsg::SetVoteProxy {
+            proxy: Some(ADDR2.to_string()),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg_proxy).unwrap();
+        assert_eq!(
+            res.attributes[..3],
+            vec![
+                attr("action", "set_vote_proxy"),
+                attr("address", ADDR1),
+                attr("proxy", ADDR2),
+            ]
+        );
+        let _res = execute(deps.as_mut(), env.clone(), proxy.clone(), msg.clone()).unwrap();
+
+        // The ballot is recorded for ADDR1
+        let msg_query = QueryMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            address: ADDR1.to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg_query).unwrap();
+        let res: VoteResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.vote.unwrap().option, "Soon");
+
+        // Revoked
+        let msg_proxy = ExecuteMsg::SetVoteProxy { proxy: None };
+        let res = execute(deps.as_mut(), env.clone(), info, msg_proxy).unwrap();
+        assert!(res.attributes.contains(&attr("proxy", "none")));
+        let err = execute(deps.as_mut(), env, proxy, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+    }
+
+    #[test]
+    fn test_execute_grant_revoke_role() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let owner = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), owner.clone(), msg).unwrap();
+
+        // Owner makes ADDR2 a manager
+        let msg = ExecuteMsg::GrantRole {
+            address: ADDR2.to_string(),
+            role: Role::Manager,
+        };
+        let res = execute(deps.as_mut(), env.clone(), owner.clone(), msg).unwrap();
+        assert_eq!(
+            res.attributes[..3],
+            vec![
+                attr("action", "grant_role"),
+                attr("role", "manager"),
+                attr("address", ADDR2),
+            ]
+        );
+
+        // Managers can grant moderators but not managers
+        let manager = mock_info(ADDR2, &[]);
+        let msg = ExecuteMsg::GrantRole {
+            address: "addr3".to_string(),
+            role: Role::Manager,
+        };
+        let err = execute(deps.as_mut(), env.clone(), manager.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        let msg = ExecuteMsg::GrantRole {
+            address: "addr3".to_string(),
+            role: Role::Moderator,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), manager.clone(), msg).unwrap();
+
+        // Moderators cannot close polls of others
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), owner.clone(), msg).unwrap();
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr3", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotPollCreator { .. }));
+
+        // Managers cannot revoke other managers
+        let msg = ExecuteMsg::RevokeRole {
+            address: ADDR2.to_string(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), manager, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+
+        // Revoked managers lose their rights
+        let msg = ExecuteMsg::RevokeRole {
+            address: ADDR2.to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), owner, msg).unwrap();
+        assert_eq!(
+            res.attributes[..3],
+            vec![
+                attr("action", "revoke_role"),
+                attr("role", "manager"),
+                attr("address", ADDR2),
+            ]
+        );
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let err = execute(deps.as_mut(), env, mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::NotPollCreator { .. }));
+    }
+
+    #[test]
+    fn test_execute_update_ownership() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Owner offers the ownership to ADDR2
+        let msg = ExecuteMsg::UpdateOwnership(Action::TransferOwnership {
+            new_owner: ADDR2.to_string(),
+            expiry: None,
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::Ownership {}).unwrap();
+        let res: Ownership<Addr> = from_binary(&bin).unwrap();
+        assert_eq!(res.owner, Some(Addr::unchecked(ADDR1)));
+        assert_eq!(res.pending_owner, Some(Addr::unchecked(ADDR2)));
+
+        // Only the pending owner can accept
+        let msg = ExecuteMsg::UpdateOwnership(Action::AcceptOwnership);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr3", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Ownership(OwnershipError::NotPendingOwner)
+        ));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        let bin = query(deps.as_ref(), env, QueryMsg::Ownership {}).unwrap();
+        let res: Ownership<Addr> = from_binary(&bin).unwrap();
+        assert_eq!(res.owner, Some(Addr::unchecked(ADDR2)));
+        assert_eq!(res.pending_owner, None);
+    }
+
+    #[test]
+    fn test_execute_update_config() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Only managers and the owner
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate {
+            max_options: Some(2),
+            allow_public_creation: Some(false),
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_question_length: None,
+            max_option_length: None,
+            debug_events: None,
+            voted_nft: None,
+            participation_denom: None,
+            nois_proxy: None,
+            croncat_tasks: None,
+            dao: None,
+        });
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Public creation disabled
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Yes or no?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // New options limit
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("002".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::TooManyOptions { max: 2 }));
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("002".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::TooFewOptions { min: 2 }));
+
+        // Bounds must stay consistent
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate {
+            min_options: Some(3),
+            max_options: None,
+            allow_public_creation: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            max_question_length: None,
+            max_option_length: None,
+            debug_events: None,
+            voted_nft: None,
+            participation_denom: None,
+            nois_proxy: None,
+            croncat_tasks: None,
+            dao: None,
+        });
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidOptionBounds {}));
+    }
+
+    #[test]
+    fn test_execute_set_paused() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Only managers can pause
+        let msg = ExecuteMsg::SetPaused { paused: true };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Voting and creation are rejected
+        let msg_vote = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Now".to_string(),
+            on_behalf_of: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg_vote.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::ContractPaused {}));
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("002".parse().unwrap()),
+            question: "rgb?".to_string(),
+            options: vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::ContractPaused {}));
+
+        // Back to normal after unpausing
+        let msg = ExecuteMsg::SetPaused { paused: false };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env, info, msg_vote).unwrap();
+    }
+
+    #[test]
+    fn test_execute_ban_address() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Regular addresses cannot ban
+        let msg = ExecuteMsg::BanAddress {
+            address: ADDR2.to_string(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr3", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Banned address can neither vote nor create polls
+        let msg_vote = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Now".to_string(),
+            on_behalf_of: None,
+        };
+        let banned = mock_info(ADDR2, &[]);
+        let err =
+            execute(deps.as_mut(), env.clone(), banned.clone(), msg_vote.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Banned { .. }));
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("002".parse().unwrap()),
+            question: "rgb?".to_string(),
+            options: vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), banned.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::Banned { .. }));
+
+        // Unbanned
+        let msg = ExecuteMsg::UnbanAddress {
+            address: ADDR2.to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let _res = execute(deps.as_mut(), env, banned, msg_vote).unwrap();
+    }
+
+    #[test]
+    fn test_execute_remove_poll() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // ADDR2 is a moderator
+        let msg = ExecuteMsg::GrantRole {
+            address: ADDR2.to_string(),
+            role: Role::Moderator,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Buy my token?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("addr3", &[]), msg).unwrap();
+
+        // The creator is not a moderator
+        let msg = ExecuteMsg::RemovePoll {
+            poll_id: "001".parse().unwrap(),
+            reason: "Spam".to_string(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr3", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap();
+
+        // The removal is public
+        let msg_query = QueryMsg::Removal {
+            poll_id: "001".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg_query).unwrap();
+        let res: RemovalResponse = from_binary(&bin).unwrap();
+        let removal = res.removal.unwrap();
+        assert_eq!(removal.moderator, Addr::unchecked(ADDR2));
+        assert_eq!(removal.reason, "Spam");
+
+        // Removed polls take no votes
+        let msg_vote = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Yes".to_string(),
+            on_behalf_of: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info, msg_vote).unwrap_err();
+        assert!(matches!(err, ContractError::PollClosed { .. }));
+
+        let err = execute(deps.as_mut(), env, mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::PollRemoved { .. }));
+    }
+
+    #[test]
+    fn test_execute_create_poll_fee() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: Some(coin(1000, "ujuno")),
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        // No funds
+        let err = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Payment(PaymentError::NoFunds {})
+        ));
+        // Not enough
+        let info = mock_info(ADDR1, &[coin(500, "ujuno")]);
+        let err = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientFee { .. }));
+        // Wrong denom
+        let info = mock_info(ADDR1, &[coin(1000, "uatom")]);
+        let err = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Payment(_)));
+
+        let info = mock_info(ADDR1, &[coin(1000, "ujuno")]);
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            FEES.load(deps.as_ref().storage, "ujuno".to_string())
+                .unwrap(),
+            Uint128::new(1000)
+        );
+    }
+
+    #[test]
+    fn test_sudo() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("some_id".parse().unwrap()),
+            question: "Yes or no?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        let msg = SudoMsg::RemovePoll {
+            poll_id: "some_id".parse().unwrap(),
+            reason: "Governance proposal".to_string(),
+        };
+        let _res = sudo(deps.as_mut(), env.clone(), msg).unwrap();
+        let msg = QueryMsg::Removal {
+            poll_id: "some_id".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: RemovalResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.removal.unwrap().moderator, env.contract.address);
+
+        let msg = SudoMsg::SetAdmin {
+            admin: Some(ADDR2.to_string()),
+        };
+        let _res = sudo(deps.as_mut(), env.clone(), msg).unwrap();
+        let ownership = cw_ownable::get_ownership(&deps.storage).unwrap();
+        assert_eq!(ownership.owner, Some(Addr::unchecked(ADDR2)));
+
+        let msg = SudoMsg::SetAdmin { admin: None };
+        let _res = sudo(deps.as_mut(), env, msg).unwrap();
+        let ownership = cw_ownable::get_ownership(&deps.storage).unwrap();
+        assert_eq!(ownership.owner, None);
+    }
+
+    #[test]
+    fn test_execute_emergency_remove() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        for poll_id in ["spam1", "spam2", "legit"] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Yes or no?".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: Some(vec!["spam".to_string()]),
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+            let msg = ExecuteMsg::Vote {
+                poll_id: poll_id.parse().unwrap(),
+                vote: "Yes".to_string(),
+                on_behalf_of: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+        }
+
+        let msg = ExecuteMsg::EmergencyRemove {
+            poll_ids: vec!["spam1".parse().unwrap(), "spam2".parse().unwrap()],
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Polls are gone, the rest is untouched
+        let bin = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::AllPolls {
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls.len(), 1);
+
+        // Ballots are pruned apart, only for deleted polls
+        let prune = |poll_id: &str| ExecuteMsg::PruneBallots {
+            poll_id: poll_id.parse().unwrap(),
+            limit: Some(10),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            prune("legit"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::PollNotDeleted {}));
+        // Their id can't be reused until then
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("spam1".parse().unwrap()),
+            question: "Yes or no?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::PollIdTaken { .. }));
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            prune("spam1"),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2], ("pruned", "1"));
+        for (poll_id, voted) in [("spam1", false), ("spam2", true), ("legit", true)] {
+            let msg = QueryMsg::Vote {
+                poll_id: poll_id.parse().unwrap(),
+                address: ADDR2.to_string(),
+            };
+            let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+            let res: VoteResponse = from_binary(&bin).unwrap();
+            assert_eq!(res.vote.is_some(), voted);
+        }
+        // Along with their tag entries
+        let msg = QueryMsg::PollsByTag {
+            tag: "spam".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls.len(), 1);
+    }
+    #[test]
+    fn test_execute_emergency_remove_recreate() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let payout = CosmosMsg::Bank(BankMsg::Send {
+            to_address: "grantee".to_string(),
+            amount: vec![coin(10, "ujuno")],
+        });
+        let create = |actions: Option<PollActions>| ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Fund the grant?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: Some(mock_env().block.time.plus_seconds(100)),
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions,
+            finalize_fee: None,
+        };
+        let msg = create(Some(PollActions {
+            option: "Yes".to_string(),
+            msgs: vec![payout],
+            quorum: None,
+            threshold: None,
+        }));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::FundReward {
+            poll_id: "001".parse().unwrap(),
+        };
+        let sponsor = mock_info("sponsor", &[coin(300, "ujuno")]);
+        let _res = execute(deps.as_mut(), env.clone(), sponsor, msg).unwrap();
+
+        // The rewards go back to the sponsor with the poll
+        let msg = ExecuteMsg::EmergencyRemove {
+            poll_ids: vec!["001".parse().unwrap()],
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "sponsor".to_string(),
+                amount: vec![coin(300, "ujuno")],
+            })]
+        );
+
+        // Someone else takes the id, nothing of the removed poll is left
+        let creator = mock_info(ADDR2, &[]);
+        let _res = execute(deps.as_mut(), env.clone(), creator.clone(), create(None)).unwrap();
+        let msg = QueryMsg::PollActions {
+            poll_id: "001".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        assert_eq!(from_binary::<Option<PollActions>>(&bin).unwrap(), None);
+        let msg = QueryMsg::RewardPool {
+            poll_id: "001".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        assert!(from_binary::<RewardPool>(&bin).unwrap().is_empty());
+        let msg = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Yes".to_string(),
+            on_behalf_of: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), creator.clone(), msg).unwrap();
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let res = execute(deps.as_mut(), env, creator, msg).unwrap();
+        assert!(res.messages.is_empty());
+    }
+
+    #[test]
+    fn test_query_audit_log() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for poll_id in ["001", "002"] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Yes or no?".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+        }
+
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate {
+            min_options: None,
+            max_options: Some(4),
+            allow_public_creation: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            max_question_length: None,
+            max_option_length: None,
+            debug_events: None,
+            voted_nft: None,
+            participation_denom: None,
+            nois_proxy: None,
+            croncat_tasks: None,
+            dao: None,
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::SetPaused { paused: true };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::SetPaused { paused: false };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        // The owner closing a poll is logged, its creator closing it isn't
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "002".parse().unwrap(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        let msg = QueryMsg::AuditLog {
+            start_after: None,
+            limit: Some(2),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AuditLogResponse = from_binary(&bin).unwrap();
+        let actions: Vec<_> = res
+            .entries
+            .iter()
+            .map(|(_, entry)| entry.action.as_str())
+            .collect();
+        assert_eq!(actions, vec!["update_config", "pause"]);
+        assert_eq!(res.next_start_after, Some(1));
+        let msg = QueryMsg::AuditLog {
+            start_after: res.next_start_after,
+            limit: Some(2),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AuditLogResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.entries[1],
+            (
+                3,
+                AuditEntry {
+                    actor: Addr::unchecked(ADDR1),
+                    action: "close_poll".to_string(),
+                    poll_id: Some("1".to_string()),
+                    height: env.block.height,
+                    time: env.block.time,
+                }
+            )
+        );
+        assert_eq!(res.next_start_after, Some(3));
+        let msg = QueryMsg::AuditLog {
+            start_after: res.next_start_after,
+            limit: Some(2),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: AuditLogResponse = from_binary(&bin).unwrap();
+        assert!(res.entries.is_empty());
+    }
+
+    #[test]
+    fn test_execute_reclaim_deposit() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: Some(coin(100, "ujuno")),
+            cw20_creation_fee: None,
+            creation_deposit: Some(coin(1000, "ujuno")),
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        for poll_id in ["001", "002"] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Wen moon?".to_string(),
+                options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            // The fee alone is not enough
+            let creator = mock_info(ADDR2, &[coin(100, "ujuno")]);
+            let err = execute(deps.as_mut(), env.clone(), creator, msg.clone()).unwrap_err();
+            assert!(matches!(err, ContractError::InsufficientFee { .. }));
+            let creator = mock_info(ADDR2, &[coin(1100, "ujuno")]);
+            let _res = execute(deps.as_mut(), env.clone(), creator, msg).unwrap();
+        }
+        // Only the fees are collected
+        assert_eq!(
+            FEES.load(deps.as_ref().storage, "ujuno".to_string())
+                .unwrap(),
+            Uint128::new(200)
+        );
+
+        let msg = ExecuteMsg::ReclaimDeposit {
+            poll_id: "001".parse().unwrap(),
+        };
+        let creator = mock_info(ADDR2, &[]);
+        let err = execute(deps.as_mut(), env.clone(), creator.clone(), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::PollOpen { .. }));
+
+        // Closed polls get the deposit back, once
+        let msg_close = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), creator.clone(), msg_close).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NotPollCreator { .. }));
+        let res = execute(deps.as_mut(), env.clone(), creator.clone(), msg.clone()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: ADDR2.to_string(),
+                amount: vec![coin(1000, "ujuno")],
+            })
+        );
+        assert_eq!(
+            res.attributes[..4],
+            vec![
+                attr("action", "reclaim_deposit"),
+                attr("poll_id", "1"),
+                attr("creator", ADDR2),
+                attr("amount", "1000ujuno"),
+            ]
+        );
+        let err = execute(deps.as_mut(), env.clone(), creator.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoDeposit { .. }));
+
+        // Removed polls lose it, the whole deposit is slashed by default
+        let msg_remove = ExecuteMsg::RemovePoll {
+            poll_id: "002".parse().unwrap(),
+            reason: "Spam".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg_remove).unwrap();
+        let msg = ExecuteMsg::ReclaimDeposit {
+            poll_id: "002".parse().unwrap(),
+        };
+        let err = execute(deps.as_mut(), env, creator, msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoDeposit { .. }));
+        assert_eq!(
+            FEES.load(deps.as_ref().storage, "ujuno".to_string())
+                .unwrap(),
+            Uint128::new(1200)
+        );
+    }
+
+    #[test]
+    fn test_execute_remove_poll_slash_deposit() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: Some(coin(1000, "ujuno")),
+            deposit_slash_ratio: Some(Decimal::percent(30)),
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Buy my token?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let creator = mock_info(ADDR2, &[coin(1000, "ujuno")]);
+        let _res = execute(deps.as_mut(), env.clone(), creator, msg).unwrap();
+
+        let msg = ExecuteMsg::RemovePoll {
+            poll_id: "001".parse().unwrap(),
+            reason: "Scam".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert!(res.attributes.contains(&attr("slashed", "300")));
+
+        // 30% to the treasury, the creator gets the rest back
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::PendingFees {}).unwrap();
+        let res: PendingFeesResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.fees, vec![coin(300, "ujuno")]);
+        let msg = ExecuteMsg::ReclaimDeposit {
+            poll_id: "001".parse().unwrap(),
+        };
+        let res = execute(deps.as_mut(), env, mock_info(ADDR2, &[]), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: ADDR2.to_string(),
+                amount: vec![coin(700, "ujuno")],
+            })
+        );
+    }
+
+    #[test]
+    fn test_execute_receive_create_poll() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: Some(Cw20Coin {
+                address: "token".to_string(),
+                amount: Uint128::new(1000),
+            }),
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Creating directly is not possible, the fee is paid in the token
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientCw20Fee { .. }));
+
+        let receive = |amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: ADDR2.to_string(),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::CreatePoll {
+                    slug: Some("001".parse().unwrap()),
+                    question: "Wen moon?".to_string(),
+                    options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+                    veto: None,
+                    vote_deposit: None,
+                    expires_at: None,
+                    tags: None,
+                    hide_results: None,
+                    lazy_tally: None,
+                    vote_token: None,
+                    tie_break: None,
+                    actions: None,
+                })
+                .unwrap(),
+            })
+        };
+
+        // Other tokens are not accepted
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("other", &[]),
+            receive(1000),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::UnsupportedToken { .. }));
+        // Not enough
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("token", &[]),
+            receive(999),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientCw20Fee { .. }));
+
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("token", &[]),
+            receive(1000),
+        )
+        .unwrap();
+
+        // The poll belongs to the cw20 sender
+        let msg = QueryMsg::Poll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.poll.unwrap().creator, Addr::unchecked(ADDR2));
+
+        let bin = query(deps.as_ref(), env, QueryMsg::PendingFees {}).unwrap();
+        let res: PendingFeesResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.cw20_fees,
+            vec![Cw20CoinVerified {
+                address: Addr::unchecked("token"),
+                amount: Uint128::new(1000),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_execute_receive_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string()],
+            veto: None,
+            vote_deposit: Some(coin(10, "ujuno")),
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: Some("token".to_string()),
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        // Token votes can't pay a native deposit
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::VoteDepositWithToken {}));
+        if let ExecuteMsg::CreatePoll { vote_deposit, .. } = &mut msg {
+            *vote_deposit = None;
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Voting without the token is not possible
+        let msg = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Now".to_string(),
+            on_behalf_of: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::TokenVoteRequired { .. }));
+
+        let send = |option: &str, amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: ADDR2.to_string(),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::Vote {
+                    poll_id: "001".parse().unwrap(),
+                    option: option.to_string(),
+                })
+                .unwrap(),
+            })
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("other", &[]),
+            send("Now", 100),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::UnsupportedToken { .. }));
+
+        // The amount is the weight, sending more adds to it and can move the ballot
+        let token = mock_info("token", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), token.clone(), send("Now", 100)).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), token, send("Soon", 50)).unwrap();
+        assert!(res.attributes.contains(&attr("previous_option", "Now")));
+        for (option, votes) in [("Now", 0), ("Soon", 150)] {
+            let msg = QueryMsg::OptionTally {
+                poll_id: "001".parse().unwrap(),
+                option: option.to_string(),
+            };
+            let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+            let res: OptionTallyResponse = from_binary(&bin).unwrap();
+            assert_eq!(res.votes, Uint128::new(votes));
+        }
+
+        // Escrowed until the poll is finalized
+        let claim = ExecuteMsg::ClaimDeposit {
+            poll_id: "001".parse().unwrap(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            claim.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::PollOpen { .. }));
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            claim.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: ADDR2.to_string(),
+                    amount: Uint128::new(150),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        assert!(res.attributes.contains(&attr("escrow_token", "token")));
+        assert!(res.attributes.contains(&attr("escrow_amount", "150")));
+        // Only once
+        let err = execute(deps.as_mut(), env, mock_info(ADDR2, &[]), claim).unwrap_err();
+        assert!(matches!(err, ContractError::NoDeposit { .. }));
+    }
+
+    #[test]
+    fn test_execute_claim_reward() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for poll_id in ["001", "002"] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Wen moon?".to_string(),
+                options: vec!["Now".to_string(), "Soon".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        let fund = |poll_id: &str| ExecuteMsg::FundReward {
+            poll_id: poll_id.parse().unwrap(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), fund("001")).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Payment(PaymentError::NoFunds {})
+        ));
+        let sponsor = mock_info(ADDR1, &[coin(100, "ujuno")]);
+        let _res = execute(deps.as_mut(), env.clone(), sponsor, fund("001")).unwrap();
+        let sponsor = mock_info(ADDR1, &[coin(50, "ujuno")]);
+        let _res = execute(deps.as_mut(), env.clone(), sponsor, fund("002")).unwrap();
+        // Sponsors can also send cw20 tokens
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "sponsor".to_string(),
+            amount: Uint128::new(30),
+            msg: to_binary(&ReceiveMsg::FundReward {
+                poll_id: "001".parse().unwrap(),
+            })
+            .unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("token", &[]), msg).unwrap();
+        let msg = QueryMsg::RewardPool {
+            poll_id: "001".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: RewardPool = from_binary(&bin).unwrap();
+        assert_eq!(res.native, vec![coin(100, "ujuno")]);
+        assert_eq!(res.cw20[0].amount, Uint128::new(30));
+
+        for (voter, option) in [(ADDR1, "Now"), (ADDR2, "Soon"), ("addr3", "Soon")] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: "001".parse().unwrap(),
+                vote: option.to_string(),
+                on_behalf_of: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+        let claim = |poll_ids: &[&str]| ExecuteMsg::ClaimReward {
+            poll_ids: poll_ids.iter().map(|id| id.parse().unwrap()).collect(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            claim(&["001"]),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::PollOpen { .. }));
+        for poll_id in ["001", "002"] {
+            let msg = ExecuteMsg::ClosePoll {
+                poll_id: poll_id.parse().unwrap(),
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        let sponsor = mock_info(ADDR2, &[coin(10, "ujuno")]);
+        let err = execute(deps.as_mut(), env.clone(), sponsor, fund("001")).unwrap_err();
+        assert!(matches!(err, ContractError::PollClosed { .. }));
+
+        // A third of each pool, rounded down
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            claim(&["001", "002"]),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: ADDR2.to_string(),
+                amount: vec![coin(33, "ujuno")],
+            })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: ADDR2.to_string(),
+                    amount: Uint128::new(10),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            claim(&["001"]),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NoReward {}));
+
+        // Without votes the sponsor gets its part back, in the same batch
+        let res = execute(deps.as_mut(), env, info, claim(&["001", "002"])).unwrap();
+        assert!(res.attributes.contains(&attr("polls", "2")));
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: ADDR1.to_string(),
+                amount: vec![coin(83, "ujuno")],
+            })
+        );
+    }
+
+    #[test]
+    fn test_execute_lottery() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let voters = [ADDR1, ADDR2, "addr3"];
+        for voter in voters {
+            let msg = ExecuteMsg::Vote {
+                poll_id: "001".parse().unwrap(),
+                vote: "Now".to_string(),
+                on_behalf_of: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        let start = ExecuteMsg::StartLottery {
+            poll_id: "001".parse().unwrap(),
+            winners: 2,
+            prize: coin(10, "ujuno"),
+        };
+        let sponsor = mock_info(ADDR1, &[coin(25, "ujuno")]);
+        let err = execute(deps.as_mut(), env.clone(), sponsor.clone(), start.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::RandomnessUnavailable {}));
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate {
+            min_options: None,
+            max_options: None,
+            allow_public_creation: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            max_question_length: None,
+            max_option_length: None,
+            debug_events: None,
+            voted_nft: None,
+            participation_denom: None,
+            nois_proxy: Some("nois".to_string()),
+            croncat_tasks: None,
+            dao: None,
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        // Only once the poll is finalized
+        let err = execute(deps.as_mut(), env.clone(), sponsor.clone(), start.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::PollOpen { .. }));
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &[coin(15, "ujuno")]),
+            start.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientFee { .. }));
+        // Prizes overflowing are an error, not a panic
+        let huge = ExecuteMsg::StartLottery {
+            poll_id: "001".parse().unwrap(),
+            winners: 2,
+            prize: Coin {
+                denom: "ujuno".to_string(),
+                amount: Uint128::MAX,
+            },
+        };
+        let err = execute(deps.as_mut(), env.clone(), sponsor.clone(), huge).unwrap_err();
+        assert!(matches!(err, ContractError::Std(StdError::Overflow { .. })));
+
+        // Above the prizes pays the randomness
+        let res = execute(deps.as_mut(), env.clone(), sponsor.clone(), start.clone()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "nois".to_string(),
+                msg: to_binary(&NoisProxyExecuteMsg::GetNextRandomness {
+                    job_id: "1".to_string(),
+                })
+                .unwrap(),
+                funds: vec![coin(5, "ujuno")],
+            })
+        );
+        let err = execute(deps.as_mut(), env.clone(), sponsor, start).unwrap_err();
+        assert!(matches!(err, ContractError::LotteryExists { .. }));
+
+        let callback = ExecuteMsg::NoisReceive {
+            callback: NoisCallback {
+                job_id: "1".to_string(),
+                published: env.block.time,
+                randomness: HexBinary::from([7; 32]),
+            },
+        };
+        let err = execute(deps.as_mut(), env.clone(), info, callback.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        let nois = mock_info("nois", &[]);
+        let res = execute(deps.as_mut(), env.clone(), nois.clone(), callback.clone()).unwrap();
+        let msg = QueryMsg::Lottery {
+            poll_id: "001".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let lottery: Lottery = from_binary(&bin).unwrap();
+        let drawn = lottery.drawn.unwrap();
+        assert_eq!(drawn.len(), 2);
+        assert_ne!(drawn[0], drawn[1]);
+        for (winner, msg) in drawn.iter().zip(&res.messages) {
+            assert!(voters.contains(&winner.as_str()));
+            assert_eq!(
+                msg.msg,
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: winner.to_string(),
+                    amount: vec![coin(10, "ujuno")],
+                })
+            );
+        }
+        // The randomness is used once
+        let err = execute(deps.as_mut(), env, nois, callback).unwrap_err();
+        assert!(matches!(err, ContractError::UnknownRandomnessJob { .. }));
+    }
+
+    #[test]
+    fn test_execute_close_poll_tie_break() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let create = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: Some(TieBreak::Random),
+            actions: None,
+            finalize_fee: None,
+        };
+        // The draw needs an oracle
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), create.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::RandomnessUnavailable {}));
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate {
+            min_options: None,
+            max_options: None,
+            allow_public_creation: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            max_question_length: None,
+            max_option_length: None,
+            debug_events: None,
+            voted_nft: None,
+            participation_denom: None,
+            nois_proxy: Some("nois".to_string()),
+            croncat_tasks: Some("croncat".to_string()),
+            dao: None,
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), create.clone()).unwrap();
+        // CronCat wouldn't pay the randomness
+        let msg = ExecuteMsg::ScheduleFinalize {
+            poll_id: "001".parse().unwrap(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::RandomTieBreakScheduled { .. }));
+        // Funds only pay a randomness request
+        let mut msg = create;
+        if let ExecuteMsg::CreatePoll { slug, .. } = &mut msg {
+            *slug = Some("002".parse().unwrap());
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "002".parse().unwrap(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &[coin(5, "ujuno")]),
+            msg,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::UnexpectedFunds {}));
+        for (voter, option) in [(ADDR1, "Now"), (ADDR2, "Soon")] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: "001".parse().unwrap(),
+                vote: option.to_string(),
+                on_behalf_of: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        // Closing on a tie requests the randomness with the sent funds
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &[coin(5, "ujuno")]),
+            msg,
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("tie_break_job_id", "1")));
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "nois".to_string(),
+                msg: to_binary(&NoisProxyExecuteMsg::GetNextRandomness {
+                    job_id: "1".to_string(),
+                })
+                .unwrap(),
+                funds: vec![coin(5, "ujuno")],
+            })
+        );
+        let results = || QueryMsg::PollResults {
+            poll_id: "001".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), results()).unwrap();
+        let res: PollResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.winner, None);
+
+        let msg = ExecuteMsg::NoisReceive {
+            callback: NoisCallback {
+                job_id: "1".to_string(),
+                published: env.block.time,
+                randomness: HexBinary::from([7; 32]),
+            },
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("nois", &[]), msg).unwrap();
+        let bin = query(deps.as_ref(), env, results()).unwrap();
+        let results: PollResultsResponse = from_binary(&bin).unwrap();
+        let winner = results.winner.unwrap();
+        assert!(["Now", "Soon"].contains(&winner.as_str()));
+        assert!(res.attributes.contains(&attr("winner", winner)));
+        assert!(results.tie);
+        assert_eq!(results.tie_break, TieBreak::Random);
+    }
+
+    #[test]
+    fn test_execute_schedule_finalize() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate {
+            min_options: None,
+            max_options: None,
+            allow_public_creation: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            max_question_length: None,
+            max_option_length: None,
+            debug_events: None,
+            voted_nft: None,
+            participation_denom: None,
+            nois_proxy: None,
+            croncat_tasks: Some("croncat".to_string()),
+            dao: None,
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let expires_at = env.block.time.plus_seconds(100);
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: Some(expires_at),
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // The sent funds pay the task
+        let schedule = ExecuteMsg::ScheduleFinalize {
+            poll_id: "001".parse().unwrap(),
+        };
+        let sponsor = mock_info(ADDR1, &[coin(50, "ujuno")]);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            sponsor.clone(),
+            schedule.clone(),
+        )
+        .unwrap();
+        let finalize = WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_binary(&ExecuteMsg::FinalizeExpired {
+                poll_id: "1".parse().unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        };
+        let task = CroncatTaskRequest {
+            interval: CroncatInterval::Once,
+            boundary: Some(CroncatBoundary::Time {
+                start: Some(expires_at),
+                end: None,
+            }),
+            stop_on_fail: true,
+            actions: vec![CroncatAction {
+                msg: finalize.into(),
+                gas_limit: Some(1_000_000),
+            }],
+        };
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_on_success(
+                WasmMsg::Execute {
+                    contract_addr: "croncat".to_string(),
+                    msg: to_binary(&CroncatExecuteMsg::CreateTask {
+                        task: Box::new(task),
+                    })
+                    .unwrap(),
+                    funds: vec![coin(50, "ujuno")],
+                },
+                3,
+            )]
+        );
+        let msg = Reply {
+            id: 3,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(Binary::from(b"hash")),
+            }),
+        };
+        let res = reply(deps.as_mut(), env.clone(), msg).unwrap();
+        assert!(res.attributes.contains(&attr("task_hash", "hash")));
+        let err = execute(deps.as_mut(), env.clone(), sponsor.clone(), schedule).unwrap_err();
+        assert!(matches!(err, ContractError::FinalizeScheduled { .. }));
+
+        // Closing early cancels the task, a failing removal doesn't revert it
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.messages[0],
+            SubMsg::reply_on_error(
+                WasmMsg::Execute {
+                    contract_addr: "croncat".to_string(),
+                    msg: to_binary(&CroncatExecuteMsg::RemoveTask {
+                        task_hash: "hash".to_string(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                },
+                6,
+            )
+        );
+        let msg = Reply {
+            id: 6,
+            result: SubMsgResult::Err("task not found".to_string()),
+        };
+        let res = reply(deps.as_mut(), env.clone(), msg).unwrap();
+        assert!(res
+            .attributes
+            .contains(&attr("action", "cancel_finalize_failed")));
+
+        // The task can be registered along with the poll, the rest of the
+        // funds pays the creation
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("002".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: Some(expires_at),
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: Some(coin(50, "ujuno")),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &[coin(10, "ujuno")]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientFee { .. }));
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &[coin(60, "ujuno")]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::UnexpectedFunds { .. }));
+        let mut no_expiration = msg.clone();
+        if let ExecuteMsg::CreatePoll {
+            slug, expires_at, ..
+        } = &mut no_expiration
+        {
+            *slug = Some("003".parse().unwrap());
+            *expires_at = None;
+        }
+        let err = execute(deps.as_mut(), env.clone(), sponsor.clone(), no_expiration).unwrap_err();
+        assert!(matches!(err, ContractError::NoExpiration { .. }));
+        let res = execute(deps.as_mut(), env.clone(), sponsor, msg).unwrap();
+        let create = &res.messages.last().unwrap();
+        assert_eq!(create.id, 3);
+        assert!(matches!(
+            &create.msg,
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, funds, .. })
+                if contract_addr == "croncat" && funds == &vec![coin(50, "ujuno")]
+        ));
+
+        // Anyone finalizes once expired
+        let finalize = ExecuteMsg::FinalizeExpired {
+            poll_id: "002".parse().unwrap(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            finalize.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::PollNotExpired { .. }));
+        let mut later = env;
+        later.block.time = expires_at;
+        let _res = execute(
+            deps.as_mut(),
+            later.clone(),
+            mock_info(ADDR2, &[]),
+            finalize,
+        )
+        .unwrap();
+        let msg = QueryMsg::Poll {
+            poll_id: "002".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), later, msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.poll.unwrap().status, PollStatus::Closed);
+    }
+
+    #[test]
+    fn test_execute_hooks() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let add = ExecuteMsg::AddHook {
+            addr: "dao".to_string(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            add.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), add.clone()).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), add).unwrap_err();
+        assert!(matches!(err, ContractError::HookAlreadyRegistered { .. }));
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::Hooks {}).unwrap();
+        let res: HooksResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.hooks, vec!["dao".to_string()]);
+
+        let hook = |msg: PollHookMsg| {
+            SubMsg::reply_on_error(
+                WasmMsg::Execute {
+                    contract_addr: "dao".to_string(),
+                    msg: to_binary(&PollHookExecuteMsg::PollHook(msg)).unwrap(),
+                    funds: vec![],
+                },
+                4,
+            )
+        };
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![hook(PollHookMsg::PollCreated {
+                poll_id: "1".to_string(),
+                creator: ADDR1.to_string(),
+            })]
+        );
+
+        // Repeating a ballot isn't news
+        let vote = |option: &str| ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: option.to_string(),
+            on_behalf_of: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), vote("Now")).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), vote("Soon")).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![hook(PollHookMsg::Voted {
+                poll_id: "1".to_string(),
+                voter: ADDR1.to_string(),
+                option: "Soon".to_string(),
+                previous_option: Some("Now".to_string()),
+            })]
+        );
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), vote("Soon")).unwrap();
+        assert!(res.messages.is_empty());
+
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![hook(PollHookMsg::PollClosed {
+                poll_id: "1".to_string(),
+                winner: Some("Soon".to_string()),
+            })]
+        );
+        // A veto closes the poll without a winner
+        let msg = ExecuteMsg::CreatePoll {
+            slug: None,
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::VetoPoll {
+            poll_id: "2".parse().unwrap(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![hook(PollHookMsg::PollClosed {
+                poll_id: "2".to_string(),
+                winner: None,
+            })]
+        );
+        // A failing hook is only reported
+        let msg = Reply {
+            id: 4,
+            result: SubMsgResult::Err("Out of gas".to_string()),
+        };
+        let res = reply(deps.as_mut(), env.clone(), msg).unwrap();
+        assert!(res.attributes.contains(&attr("action", "hook_failed")));
+
+        let remove = ExecuteMsg::RemoveHook {
+            addr: "dao".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), remove.clone()).unwrap();
+        let err = execute(deps.as_mut(), env, info, remove).unwrap_err();
+        assert!(matches!(err, ContractError::HookNotRegistered { .. }));
+    }
+
+    #[test]
+    fn test_execute_poll_actions() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let payout = CosmosMsg::Bank(BankMsg::Send {
+            to_address: "grantee".to_string(),
+            amount: vec![coin(10, "ujuno")],
+        });
+        let create =
+            |poll_id: &str, option: &str, quorum: u128, threshold: &str| ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Fund the grant?".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: Some(mock_env().block.time.plus_seconds(100)),
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: Some(PollActions {
+                    option: option.to_string(),
+                    msgs: vec![payout.clone()],
+                    quorum: Some(Uint128::new(quorum)),
+                    threshold: Some(threshold.parse().unwrap()),
+                }),
+                finalize_fee: None,
+            };
+        // Only the owner attaches messages, not even managers
+        let msg = ExecuteMsg::GrantRole {
+            address: ADDR2.to_string(),
+            role: Role::Manager,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            create("001", "Yes", 2, "0.6"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        let mut msg = create("001", "Yes", 2, "0.6");
+        if let ExecuteMsg::CreatePoll { expires_at, .. } = &mut msg {
+            *expires_at = None;
+        }
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoExpiration { .. }));
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            create("001", "Maybe", 2, "0.6"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::OptionNotFound { .. }));
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            create("001", "Yes", 2, "1.5"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidThreshold {}));
+        let msg = create("001", "Yes", 2, "0.6");
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        // Out of reach of the quorum
+        let msg = create("002", "Yes", 5, "0.6");
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = QueryMsg::PollActions {
+            poll_id: "001".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: Option<PollActions> = from_binary(&bin).unwrap();
+        assert_eq!(res.unwrap().msgs, vec![payout.clone()]);
+
+        for poll_id in ["001", "002"] {
+            for (voter, option) in [(ADDR1, "Yes"), (ADDR2, "Yes"), ("addr3", "No")] {
+                let msg = ExecuteMsg::Vote {
+                    poll_id: poll_id.parse().unwrap(),
+                    vote: option.to_string(),
+                    on_behalf_of: None,
+                };
+                let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+            }
+        }
+        let close = |poll_id: &str| ExecuteMsg::ClosePoll {
+            poll_id: poll_id.parse().unwrap(),
+        };
+        // Not before the end of the voting period
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), close("001")).unwrap_err();
+        assert!(matches!(err, ContractError::PollNotExpired { .. }));
+        let mut env = env;
+        env.block.time = env.block.time.plus_seconds(100);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), close("001")).unwrap();
+        assert!(res.attributes.contains(&attr("passed", "true")));
+        assert_eq!(res.messages, vec![SubMsg::reply_on_error(payout, 5)]);
+        let res = execute(deps.as_mut(), env, info, close("002")).unwrap();
+        assert!(res.attributes.contains(&attr("passed", "false")));
+        assert!(res.messages.is_empty());
+    }
+
+    #[test]
+    fn test_query_cw3() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let payout = CosmosMsg::Bank(BankMsg::Send {
+            to_address: "grantee".to_string(),
+            amount: vec![coin(10, "ujuno")],
+        });
+        // Proposal 1 with actions, 2 a plain poll hiding its results
+        for (actions, hide_results) in [
+            (
+                Some(PollActions {
+                    option: "Fund".to_string(),
+                    msgs: vec![payout.clone()],
+                    quorum: None,
+                    threshold: None,
+                }),
+                None,
+            ),
+            (None, Some(true)),
+        ] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: None,
+                question: "Fund the grant?".to_string(),
+                options: vec![
+                    "Fund".to_string(),
+                    "Abstain".to_string(),
+                    "Later".to_string(),
+                ],
+                veto: None,
+                vote_deposit: None,
+                expires_at: Some(env.block.time.plus_seconds(100)),
+                tags: None,
+                hide_results,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions,
+                finalize_fee: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        for (poll_id, voter, option) in [
+            ("1", ADDR1, "Fund"),
+            ("1", ADDR2, "Abstain"),
+            ("1", "addr3", "Later"),
+            ("1", "addr4", "Fund"),
+            ("2", ADDR1, "Fund"),
+        ] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: poll_id.parse().unwrap(),
+                vote: option.to_string(),
+                on_behalf_of: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "1".parse().unwrap(),
+        };
+        let mut closed = env.clone();
+        closed.block.time = env.block.time.plus_seconds(100);
+        let _res = execute(deps.as_mut(), closed, info, msg).unwrap();
+
+        // Queries as cw3 tooling sends them
+        let cw3_query = |json: String| {
+            let msg: QueryEntryMsg = from_slice(json.as_bytes()).unwrap();
+            entry::query(deps.as_ref(), mock_env(), msg).unwrap()
+        };
+        let res: Cw3ProposalResponse =
+            from_binary(&cw3_query(r#"{"proposal":{"proposal_id":1}}"#.to_string())).unwrap();
+        assert_eq!(res.title, "Fund the grant?");
+        assert_eq!(res.description, "Fund, Abstain, Later");
+        assert_eq!(res.status, Cw3Status::Executed);
+        assert_eq!(res.msgs, vec![payout]);
+        assert_eq!(
+            res.expires,
+            Expiration::AtTime(env.block.time.plus_seconds(100))
+        );
+        assert_eq!(res.proposer, Addr::unchecked(ADDR1));
+        let res: Cw3ProposalListResponse = from_binary(&cw3_query(
+            r#"{"list_proposals":{"start_after":1}}"#.to_string(),
+        ))
+        .unwrap();
+        assert_eq!(res.proposals.len(), 1);
+        assert_eq!(res.proposals[0].id, 2);
+        assert_eq!(res.proposals[0].status, Cw3Status::Open);
+        let res: Cw3ProposalListResponse = from_binary(&cw3_query(format!(
+            r#"{{"list_proposals":{{"start_after":{}}}}}"#,
+            u64::MAX
+        )))
+        .unwrap();
+        assert!(res.proposals.is_empty());
+
+        // Named like a cw3 vote, the option of the actions or any other
+        let votes: Cw3VoteListResponse = from_binary(&cw3_query(
+            r#"{"list_votes":{"proposal_id":1}}"#.to_string(),
+        ))
+        .unwrap();
+        let votes: Vec<_> = votes
+            .votes
+            .into_iter()
+            .map(|info| (info.voter, info.vote, info.weight))
+            .collect();
+        assert_eq!(
+            votes,
+            vec![
+                (ADDR1.to_string(), Cw3Vote::Yes, 1),
+                (ADDR2.to_string(), Cw3Vote::Abstain, 1),
+                ("addr3".to_string(), Cw3Vote::No, 1),
+                ("addr4".to_string(), Cw3Vote::Yes, 1),
+            ]
+        );
+        let res: Cw3VoteResponse = from_binary(&cw3_query(format!(
+            r#"{{"vote":{{"proposal_id":1,"voter":"{ADDR2}"}}}}"#
+        )))
+        .unwrap();
+        assert_eq!(res.vote.unwrap().vote, Cw3Vote::Abstain);
+        // The vote query of the contract still parses first
+        let res: VoteResponse = from_binary(&cw3_query(format!(
+            r#"{{"vote":{{"poll_id":"1","address":"{ADDR2}"}}}}"#
+        )))
+        .unwrap();
+        assert_eq!(res.vote.unwrap().option, "Abstain");
+        // Ballots of a poll hiding its results stay secret until it's closed
+        for json in [
+            format!(r#"{{"vote":{{"proposal_id":2,"voter":"{ADDR1}"}}}}"#),
+            r#"{"list_votes":{"proposal_id":2}}"#.to_string(),
+        ] {
+            let msg: QueryEntryMsg = from_slice(json.as_bytes()).unwrap();
+            entry::query(deps.as_ref(), mock_env(), msg).unwrap_err();
+        }
+        let res: Cw3ThresholdResponse =
+            from_binary(&cw3_query(r#"{"threshold":{}}"#.to_string())).unwrap();
+        assert!(matches!(
+            res,
+            Cw3ThresholdResponse::AbsolutePercentage { .. }
+        ));
+    }
+
+    #[test]
+    fn test_dao_module() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        // Voting power of the DAO at the creation of the poll, addr1 only
+        let height = env.block.height;
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "dao" => {
+                let DaoQueryMsg::VotingPowerAtHeight {
+                    address,
+                    height: at,
+                } = from_binary(msg).unwrap();
+                assert_eq!(at, Some(height));
+                let power = Uint128::new(if address == ADDR1 { 5 } else { 0 });
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&VotingPowerAtHeightResponse { power, height }).unwrap(),
+                ))
+            }
+            _ => panic!("unexpected query"),
+        });
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate {
+            min_options: None,
+            max_options: None,
+            allow_public_creation: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            max_question_length: None,
+            max_option_length: None,
+            debug_events: None,
+            voted_nft: None,
+            participation_denom: None,
+            nois_proxy: None,
+            croncat_tasks: None,
+            dao: Some("dao".to_string()),
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::Dao {}).unwrap();
+        let dao: Addr = from_binary(&bin).unwrap();
+        assert_eq!(dao, Addr::unchecked("dao"));
+
+        // Only the DAO registers the hooks
+        let add = ExecuteMsg::AddProposalHook {
+            address: "indexer".to_string(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), add.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        let dao_info = mock_info("dao", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), dao_info.clone(), add.clone()).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), dao_info.clone(), add).unwrap_err();
+        assert!(matches!(err, ContractError::HookAlreadyRegistered { .. }));
+        let add = ExecuteMsg::AddVoteHook {
+            address: "indexer".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), dao_info, add).unwrap();
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::ProposalHooks {}).unwrap();
+        let res: HooksResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.hooks, vec!["indexer".to_string()]);
+
+        let hook = |msg: DaoHookExecuteMsg| {
+            SubMsg::reply_on_error(
+                WasmMsg::Execute {
+                    contract_addr: "indexer".to_string(),
+                    msg: to_binary(&msg).unwrap(),
+                    funds: vec![],
+                },
+                4,
+            )
+        };
+        let msg = ExecuteMsg::CreatePoll {
+            slug: None,
+            question: "Fund the grant?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![hook(DaoHookExecuteMsg::ProposalHook(
+                ProposalHookMsg::NewProposal {
+                    id: 1,
+                    proposer: ADDR1.to_string(),
+                }
+            ))]
+        );
+
+        // Ballots weigh the voting power, members without any can't vote
+        let vote = ExecuteMsg::Vote {
+            poll_id: "1".parse().unwrap(),
+            vote: "Yes".to_string(),
+            on_behalf_of: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            vote.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NoVotingPower { .. }));
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), vote).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![hook(DaoHookExecuteMsg::VoteHook(VoteHookMsg::NewVote {
+                proposal_id: 1,
+                voter: ADDR1.to_string(),
+                vote: "Yes".to_string(),
+            }))]
+        );
+        let msg = QueryMsg::Vote {
+            poll_id: "1".parse().unwrap(),
+            address: ADDR1.to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: VoteResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.vote.unwrap().weight, Uint128::new(5));
+
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "1".parse().unwrap(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![hook(DaoHookExecuteMsg::ProposalHook(
+                ProposalHookMsg::ProposalStatusChanged {
+                    id: 1,
+                    old_status: "open".to_string(),
+                    new_status: "passed".to_string(),
+                }
+            ))]
+        );
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::ProposalCount {}).unwrap();
+        assert_eq!(from_binary::<u64>(&bin).unwrap(), 1);
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::NextProposalId {}).unwrap();
+        assert_eq!(from_binary::<u64>(&bin).unwrap(), 2);
+        let bin = query(deps.as_ref(), env, QueryMsg::Info {}).unwrap();
+        let res: InfoResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.info.contract, "crates.io:cw-starter");
+    }
+
+    #[test]
+    fn test_execute_withdraw_fees() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: Some(coin(1000, "ujuno")),
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Two polls paid
+        for poll_id in ["001", "002"] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Wen moon?".to_string(),
+                options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            let payer = mock_info(ADDR2, &[coin(1000, "ujuno")]);
+            let _res = execute(deps.as_mut(), env.clone(), payer, msg).unwrap();
+        }
+
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::PendingFees {}).unwrap();
+        let res: PendingFeesResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.fees, vec![coin(2000, "ujuno")]);
+
+        // Only the owner withdraws
+        let msg = ExecuteMsg::WithdrawFees {
+            to: "treasury".to_string(),
+        };
+        let _err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: vec![coin(2000, "ujuno")],
+            })
+        );
+
+        // Nothing left
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::PendingFees {}).unwrap();
+        let res: PendingFeesResponse = from_binary(&bin).unwrap();
+        assert!(res.fees.is_empty());
+        assert!(res.cw20_fees.is_empty());
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoFees {}));
+    }
+
+    #[test]
+    fn test_execute_flag_poll() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: Some(2),
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Buy my token?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // The same address only counts once
+        let msg = ExecuteMsg::FlagPoll {
+            poll_id: "001".parse().unwrap(),
+            reason: "Spam".to_string(),
+        };
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::AlreadyFlagged { .. }));
+
+        // The second flag pauses the poll
+        let res = execute(deps.as_mut(), env.clone(), mock_info("addr3", &[]), msg).unwrap();
+        assert!(res.attributes.contains(&attr("paused", "true")));
+        let msg_vote = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Yes".to_string(),
+            on_behalf_of: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg_vote.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::PollPaused { .. }));
+
+        // A moderator reviews it and lets it run again
+        let msg = ExecuteMsg::ResumePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, msg_vote).unwrap();
+
+        // Flags start over
+        let msg = ExecuteMsg::FlagPoll {
+            poll_id: "001".parse().unwrap(),
+            reason: "Still spam".to_string(),
+        };
+        let res = execute(deps.as_mut(), env, mock_info(ADDR2, &[]), msg).unwrap();
+        assert!(res.attributes.contains(&attr("flags", "1")));
+    }
+
+    #[test]
+    fn test_execute_create_poll_open_limit() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: Some(2),
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let create = |poll_id: &str| ExecuteMsg::CreatePoll {
+            slug: Some(poll_id.parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let creator = mock_info(ADDR2, &[]);
+        let _res = execute(deps.as_mut(), env.clone(), creator.clone(), create("001")).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), creator.clone(), create("002")).unwrap();
+
+        // Third open poll is rejected, other creators are not affected
+        let err = execute(deps.as_mut(), env.clone(), creator.clone(), create("003")).unwrap_err();
+        assert!(matches!(err, ContractError::TooManyOpenPolls { max: 2 }));
+        let _res = execute(deps.as_mut(), env.clone(), info, create("004")).unwrap();
+
+        // Closing one frees a slot
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), creator.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env, creator, create("003")).unwrap();
+    }
+
+    #[test]
+    fn test_execute_unexpected_funds() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // No creation fee configured
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("some_id".parse().unwrap()),
+            question: "Yes or no?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            veto: None,
+            vote_deposit: Some(coin(10, "ujuno")),
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let funds = mock_info(ADDR1, &[coin(10, "ujuno")]);
+        let err = execute(deps.as_mut(), env.clone(), funds, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::UnexpectedFunds {}));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Only the vote deposit is accepted, and only once
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".parse().unwrap(),
+            vote: "Yes".to_string(),
+            on_behalf_of: None,
+        };
+        let funds = mock_info(ADDR2, &[coin(10, "ujuno"), coin(1, "uatom")]);
+        let err = execute(deps.as_mut(), env.clone(), funds, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::UnexpectedFunds {}));
+        let funds = mock_info(ADDR2, &[coin(10, "ujuno")]);
+        let _res = execute(deps.as_mut(), env.clone(), funds.clone(), msg.clone()).unwrap();
+        let err = execute(deps.as_mut(), env, funds, msg).unwrap_err();
+        assert!(matches!(err, ContractError::UnexpectedFunds {}));
+    }
+
+    #[test]
+    fn test_execute_vote_voted_nft() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate {
+            min_options: None,
+            max_options: None,
+            allow_public_creation: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            max_question_length: None,
+            max_option_length: None,
+            debug_events: None,
+            voted_nft: Some("nft".to_string()),
+            participation_denom: None,
+            nois_proxy: None,
+            croncat_tasks: None,
+            dao: None,
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // The first ballot mints
+        let vote = |option: &str| ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: option.to_string(),
+            on_behalf_of: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), vote("Now")).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_on_error(
+                WasmMsg::Execute {
+                    contract_addr: "nft".to_string(),
+                    msg: to_binary(&Cw721ExecuteMsg::Mint {
+                        token_id: format!("1/{ADDR1}"),
+                        owner: ADDR1.to_string(),
+                        token_uri: None,
+                        extension: None,
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                },
+                1,
+            )]
+        );
+        // Changing the vote doesn't
+        let res = execute(deps.as_mut(), env.clone(), info, vote("Soon")).unwrap();
+        assert!(res.messages.is_empty());
+
+        // A failed mint leaves the vote in place
+        let msg = Reply {
+            id: 1,
+            result: SubMsgResult::Err("Unauthorized".to_string()),
+        };
+        let res = reply(deps.as_mut(), env, msg).unwrap();
+        assert!(res.attributes.contains(&attr("action", "voted_nft_failed")));
+    }
+
+    #[test]
+    fn test_execute_vote_participation_token() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let denom = format!("factory/{}/voted", env.contract.address);
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate {
+            min_options: None,
+            max_options: None,
+            allow_public_creation: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            max_question_length: None,
+            max_option_length: None,
+            debug_events: None,
+            voted_nft: None,
+            participation_denom: Some(denom.clone()),
+            nois_proxy: None,
+            croncat_tasks: None,
+            dao: None,
+        });
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        // Minting goes through the tokenfactory module, only in builds supporting it
+        if cfg!(not(feature = "tokenfactory")) {
+            assert!(matches!(
+                res.unwrap_err(),
+                ContractError::TokenfactoryUnsupported {}
+            ));
+            return;
+        }
+        res.unwrap();
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Now".to_string(),
+            on_behalf_of: None,
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let CosmosMsg::Stargate { type_url, value } = &res.messages[0].msg else {
+            panic!("Expected a stargate message");
+        };
+        assert_eq!(type_url, "/osmosis.tokenfactory.v1beta1.MsgMint");
+        // sender, amount { denom, amount } and mint_to_address fields
+        let mut expected = vec![0x0a, 15];
+        expected.extend_from_slice(b"cosmos2contract");
+        expected.extend_from_slice(&[0x12, denom.len() as u8 + 5, 0x0a, denom.len() as u8]);
+        expected.extend_from_slice(denom.as_bytes());
+        expected.extend_from_slice(&[0x12, 1, b'1', 0x1a, 5]);
+        expected.extend_from_slice(ADDR1.as_bytes());
+        assert_eq!(value.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn test_execute_vote_many() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Poll 001
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Poll 002
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("002".parse().unwrap()),
+            question: "rgb?".to_string(),
+            options: vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Both votes in one message
+        let msg = ExecuteMsg::VoteMany {
+            votes: vec![
+                ("001".parse().unwrap(), "Soon".to_string()),
+                ("002".parse().unwrap(), "Blue".to_string()),
+            ],
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(res.attributes[0], attr("action", "vote_many"));
+        let addr1 = Addr::unchecked(ADDR1);
+        assert_eq!(
+            res.events,
+            vec![
+                evt_vote("1", &addr1, "Soon", None),
+                evt_ballot_cast("1", &addr1, "Soon"),
+                evt_vote("2", &addr1, "Blue", None),
+                evt_ballot_cast("2", &addr1, "Blue"),
+            ]
+        );
+
+        for (poll_id, option) in [("1", "Soon"), ("2", "Blue")] {
+            let msg = QueryMsg::Vote {
+                poll_id: poll_id.parse().unwrap(),
+                address: ADDR1.to_string(),
+            };
+            let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+            let res: VoteResponse = from_binary(&bin).unwrap();
+            assert_eq!(res.vote.unwrap().option, option);
+        }
+
+        // A single invalid vote fails the whole batch
+        let msg = ExecuteMsg::VoteMany {
+            votes: vec![
+                ("001".parse().unwrap(), "Now".to_string()),
+                ("003".parse().unwrap(), "Yes".to_string()),
+            ],
+        };
+        let _err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+
+        // Same poll twice in one batch
+        let msg = ExecuteMsg::VoteMany {
+            votes: vec![
+                ("001".parse().unwrap(), "Now".to_string()),
+                ("001".parse().unwrap(), "Never".to_string()),
+            ],
+        };
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::AlreadyVoted { .. }));
+    }
+
+    #[test]
+    fn test_execute_vote_by_signature() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        // Signed votes derive the voter address with the prefix of the contract address
+        env.contract.address = Addr::unchecked("cosmos1contract");
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Voter signs off-chain, the relayer (ADDR2) submits
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]).unwrap();
+        let voter_pubkey = Binary::from(
+            signing_key
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes(),
+        );
+        let voter = pubkey_to_address(&env.contract.address, &voter_pubkey).unwrap();
+        let sign = |option: &str, nonce: u64| -> Binary {
+            let payload = to_vec(&SignedVotePayload {
+                contract: env.contract.address.to_string(),
+                poll_id: "001".to_string(),
+                option: option.to_string(),
+                nonce,
+            })
+            .unwrap();
+            let signature: Signature = signing_key.sign(&adr036_sign_doc(&voter, &payload));
+            Binary::from(signature.as_ref())
+        };
+        let relayer = mock_info(ADDR2, &[]);
+
+        let msg = ExecuteMsg::VoteBySignature {
+            poll_id: "001".parse().unwrap(),
+            option: "Soon".to_string(),
+            voter_pubkey: voter_pubkey.clone(),
+            signature: sign("Soon", 0),
+            nonce: 0,
+        };
+        let res = execute(deps.as_mut(), env.clone(), relayer.clone(), msg.clone()).unwrap();
+        assert_eq!(res.attributes[0], attr("action", "vote"));
+        assert_eq!(
+            res.events,
+            vec![
+                evt_vote("1", &voter, "Soon", None),
+                evt_ballot_cast("1", &voter, "Soon"),
+            ]
+        );
+
+        // The ballot belongs to the signer, not the relayer
+        let msg_query = QueryMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            address: voter.to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg_query).unwrap();
+        let res: VoteResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.vote.unwrap().option, "Soon");
+
+        // Replaying the same signed vote fails
+        let err = execute(deps.as_mut(), env.clone(), relayer.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidNonce { expected: 1 }));
+
+        // Signature over a different option is rejected
+        let msg = ExecuteMsg::VoteBySignature {
+            poll_id: "001".parse().unwrap(),
+            option: "Never".to_string(),
+            voter_pubkey,
+            signature: sign("Now", 1),
+            nonce: 1,
+        };
+        let err = execute(deps.as_mut(), env.clone(), relayer.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidSignature {}));
+
+        // The uncompressed form of the key would be a second voter
+        let msg = ExecuteMsg::VoteBySignature {
+            poll_id: "001".parse().unwrap(),
+            option: "Soon".to_string(),
+            voter_pubkey: Binary::from(
+                signing_key
+                    .verifying_key()
+                    .to_encoded_point(false)
+                    .as_bytes(),
+            ),
+            signature: sign("Soon", 0),
+            nonce: 0,
+        };
+        let err = execute(deps.as_mut(), env, relayer, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPubkey {}));
+    }
+
+    #[test]
+    fn test_query_all_polls() {
+        // Mock environment
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Poll 001
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Poll 002
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("002".parse().unwrap()),
+            question: "rgb?".to_string(),
+            options: vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Poll 003
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("003".parse().unwrap()),
+            question: "another poll?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Query process
+        let msg = QueryMsg::AllPolls {
+            start_after: None,
+            limit: None,
+            order: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap(); // Queries cannot change the state of a contract, so as_ref instead of as_mut
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls.len(), 3);
+        assert_eq!(res.next_start_after, None);
+        assert_eq!(res.total_count, Some(3));
+
+        // Next page, in poll_id order
+        let msg = QueryMsg::AllPolls {
+            start_after: Some(res.polls[0].0.clone()),
+            limit: Some(1),
+            order: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls.len(), 1);
+        assert_eq!(res.polls[0].0, "2");
+        assert_eq!(res.next_start_after, Some("2".to_string()));
+
+        // Newest ids first
+        let msg = QueryMsg::AllPolls {
+            start_after: Some("3".to_string()),
+            limit: None,
+            order: Some(OrderBy::Descending),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let poll_ids: Vec<_> = res
+            .polls
+            .iter()
+            .map(|(poll_id, _)| poll_id.as_str())
+            .collect();
+        assert_eq!(poll_ids, vec!["2", "1"]);
+    }
+
+    #[test]
+    fn test_query_polls_by_creator() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        for (poll_id, creator) in [("001", ADDR1), ("002", ADDR2), ("003", ADDR1)] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Yes or no?".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(creator, &[]), msg).unwrap();
+        }
+
+        let msg = QueryMsg::PollsByCreator {
+            creator: ADDR1.to_string(),
+            start_after: None,
+            limit: None,
+            order: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let poll_ids: Vec<_> = res
+            .polls
+            .iter()
+            .map(|(poll_id, _)| poll_id.as_str())
+            .collect();
+        assert_eq!(poll_ids, vec!["1", "3"]);
+
+        let msg = QueryMsg::PollsByCreator {
+            creator: ADDR1.to_string(),
+            start_after: Some("1".to_string()),
+            limit: Some(1),
+            order: None,
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls.len(), 1);
+        assert_eq!(res.polls[0].0, "3");
+    }
+
+    #[test]
+    fn test_query_polls_by_creator_and_status() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        for (poll_id, creator) in [
+            ("001", ADDR1),
+            ("002", ADDR2),
+            ("003", ADDR1),
+            ("004", ADDR1),
+        ] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Yes or no?".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(creator, &[]), msg).unwrap();
+        }
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        for (status, order, expected) in [
+            (PollStatus::Open, None, vec!["3", "4"]),
+            (PollStatus::Open, Some(OrderBy::Descending), vec!["4", "3"]),
+            (PollStatus::Closed, None, vec!["1"]),
+        ] {
+            let msg = QueryMsg::PollsByCreatorAndStatus {
+                creator: ADDR1.to_string(),
+                status,
+                start_after: None,
+                limit: None,
+                order,
+            };
+            let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+            let res: AllPollsResponse = from_binary(&bin).unwrap();
+            let poll_ids: Vec<_> = res
+                .polls
+                .iter()
+                .map(|(poll_id, _)| poll_id.as_str())
+                .collect();
+            assert_eq!(poll_ids, expected);
+        }
+
+        // Descending pages continue below start_after
+        let msg = QueryMsg::PollsByCreatorAndStatus {
+            creator: ADDR1.to_string(),
+            status: PollStatus::Open,
+            start_after: Some("4".to_string()),
+            limit: None,
+            order: Some(OrderBy::Descending),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls.len(), 1);
+        assert_eq!(res.polls[0].0, "3");
+    }
+
+    #[test]
+    fn test_query_polls_by_status() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        for poll_id in ["001", "002", "003"] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Yes or no?".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "002".parse().unwrap(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // The index follows status changes
+        for (status, expected) in [
+            (PollStatus::Open, vec!["1", "3"]),
+            (PollStatus::Closed, vec!["2"]),
+            (PollStatus::Paused, vec![]),
+        ] {
+            let msg = QueryMsg::PollsByStatus {
+                status,
+                start_after: None,
+                limit: None,
+                order: None,
+            };
+            let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+            let res: AllPollsResponse = from_binary(&bin).unwrap();
+            let poll_ids: Vec<_> = res
+                .polls
+                .iter()
+                .map(|(poll_id, _)| poll_id.as_str())
+                .collect();
+            assert_eq!(poll_ids, expected);
+        }
+    }
+
+    #[test]
+    fn test_query_polls_ending_before() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let now = env.block.time;
+        for (poll_id, expires_at) in [
+            ("001", Some(now.plus_seconds(300))),
+            ("002", None),
+            ("003", Some(now.plus_seconds(100))),
+            ("004", Some(now.plus_seconds(900))),
+        ] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Yes or no?".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at,
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Only open polls, the soonest first
+        let msg = QueryMsg::PollsEndingBefore {
+            timestamp: now.plus_seconds(1000),
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let poll_ids: Vec<_> = res
+            .polls
+            .iter()
+            .map(|(poll_id, _)| poll_id.as_str())
+            .collect();
+        assert_eq!(poll_ids, vec!["3", "4"]);
+
+        // Expired polls don't take votes anymore
+        env.block.time = now.plus_seconds(100);
+        let msg = ExecuteMsg::Vote {
+            poll_id: "003".parse().unwrap(),
+            vote: "Yes".to_string(),
+            on_behalf_of: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::PollExpired { .. }));
+
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("005".parse().unwrap()),
+            question: "Yes or no?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: Some(now),
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidExpiration {}));
+    }
+
+    #[test]
+    fn test_query_polls_by_id() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for poll_id in ["001", "002", "003"] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Yes or no?".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        // In the requested order, without the unknown ids
+        let msg = QueryMsg::PollsById {
+            poll_ids: vec![
+                "003".parse().unwrap(),
+                "404".parse().unwrap(),
+                "001".parse().unwrap(),
+            ],
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let poll_ids: Vec<_> = res
+            .polls
+            .iter()
+            .map(|(poll_id, _)| poll_id.as_str())
+            .collect();
+        assert_eq!(poll_ids, vec!["3", "1"]);
+
+        let msg = QueryMsg::PollsById {
+            poll_ids: vec!["001".parse().unwrap(); 31],
+        };
+        query(deps.as_ref(), env, msg).unwrap_err();
+    }
+
+    #[test]
+    fn test_query_recent_polls() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        // Slugs don't follow the creation order
+        for poll_id in ["b", "c", "a"] {
+            env.block.time = env.block.time.plus_seconds(10);
+            env.block.height += 1;
+            let msg = ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Yes or no?".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        let msg = QueryMsg::RecentPolls { limit: Some(2) };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let poll_ids: Vec<_> = res
+            .polls
+            .iter()
+            .map(|(poll_id, _)| poll_id.as_str())
+            .collect();
+        assert_eq!(poll_ids, vec!["3", "2"]);
+        assert_eq!(res.polls[0].1.created_at, env.block.time);
+        assert_eq!(res.polls[0].1.created_height, env.block.height);
+    }
+
+    #[test]
+    fn test_query_polls_by_tag() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for (poll_id, tags) in [
+            ("001", vec!["defi", "governance"]),
+            ("002", vec!["nft"]),
+            ("003", vec!["defi", "defi"]),
+        ] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Yes or no?".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: Some(tags.into_iter().map(String::from).collect()),
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        let msg = QueryMsg::PollsByTag {
+            tag: "defi".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let poll_ids: Vec<_> = res
+            .polls
+            .iter()
+            .map(|(poll_id, _)| poll_id.as_str())
+            .collect();
+        assert_eq!(poll_ids, vec!["1", "3"]);
+        // Duplicated tags are stored once
+        assert_eq!(res.polls[1].1.tags, vec!["defi".to_string()]);
+
+        // Metadata carries the tags without the options
+        let msg = QueryMsg::PollMetadata {
+            poll_id: "003".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollMetadataResponse = from_binary(&bin).unwrap();
+        let metadata = res.metadata.unwrap();
+        assert_eq!(metadata.tags, vec!["defi".to_string()]);
+        assert_eq!(metadata.status, PollStatus::Open);
+
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("004".parse().unwrap()),
+            question: "Yes or no?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: Some((0..6).map(|i| i.to_string()).collect()),
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::TooManyTags { max: 5 }));
+    }
+
+    #[test]
+    fn test_query_polls_with_prefix() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for poll_id in [
+            "community/2023/1",
+            "community/2024/1",
+            "community/2024/2",
+            "community/20245",
+            "dao/2024/1",
+        ] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Yes or no?".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        let msg = QueryMsg::PollsWithPrefix {
+            prefix: "community/2024/".to_string(),
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let poll_ids: Vec<_> = res
+            .polls
+            .iter()
+            .map(|(poll_id, _)| poll_id.as_str())
+            .collect();
+        assert_eq!(poll_ids, vec!["2", "3"]);
+    }
+
+    #[test]
+    fn test_query_open_polls_not_voted() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for poll_id in ["001", "002", "003", "004"] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Yes or no?".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        let msg = ExecuteMsg::Vote {
+            poll_id: "002".parse().unwrap(),
+            vote: "Yes".to_string(),
+            on_behalf_of: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "003".parse().unwrap(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = QueryMsg::OpenPollsNotVoted {
+            address: ADDR2.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let poll_ids: Vec<_> = res
+            .polls
+            .iter()
+            .map(|(poll_id, _)| poll_id.as_str())
+            .collect();
+        assert_eq!(poll_ids, vec!["1", "4"]);
+    }
+
+    #[test]
+    fn test_query_poll_results_with_permit() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        // Permit signers derive their address with the prefix of the contract address
+        env.contract.address = Addr::unchecked("cosmos1contract");
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let signing_key = SigningKey::from_bytes(&[9u8; 32]).unwrap();
+        let pubkey = Binary::from(
+            signing_key
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes(),
+        );
+        let creator = pubkey_to_address(&env.contract.address, &pubkey).unwrap();
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: Some(true),
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(creator.as_str(), &[]),
+            msg,
+        )
+        .unwrap();
+        let msg = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Soon".to_string(),
+            on_behalf_of: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        // Public queries don't reveal the tallies while the poll is open
+        let msg = QueryMsg::PollResults {
+            poll_id: "001".parse().unwrap(),
+        };
+        let _err = query(deps.as_ref(), env.clone(), msg).unwrap_err();
+        let msg = QueryMsg::OptionTally {
+            poll_id: "001".parse().unwrap(),
+            option: "Soon".to_string(),
+        };
+        let _err = query(deps.as_ref(), env.clone(), msg).unwrap_err();
+        // Nor the ballots
+        let msg = QueryMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            address: ADDR2.to_string(),
+        };
+        let _err = query(deps.as_ref(), env.clone(), msg).unwrap_err();
+        let msg = QueryMsg::VoteAtHeight {
+            poll_id: "001".parse().unwrap(),
+            address: ADDR2.to_string(),
+            height: env.block.height,
+        };
+        let _err = query(deps.as_ref(), env.clone(), msg).unwrap_err();
+        let votes_by_address = QueryMsg::VotesByAddress {
+            address: ADDR2.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), votes_by_address.clone()).unwrap();
+        let res: VotesByAddressResponse = from_binary(&bin).unwrap();
+        assert!(res.votes.is_empty());
+
+        let sign = |poll_id: &str| -> Binary {
+            let payload = to_vec(&ResultsPermitPayload {
+                contract: env.contract.address.to_string(),
+                poll_id: poll_id.to_string(),
+            })
+            .unwrap();
+            let signature: Signature = signing_key.sign(&adr036_sign_doc(&creator, &payload));
+            Binary::from(signature.as_ref())
+        };
+        let msg = QueryMsg::PollResultsWithPermit {
+            poll_id: "001".parse().unwrap(),
+            permit: ResultsPermit {
+                pubkey: pubkey.clone(),
+                signature: sign("001"),
+            },
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.winner, Some("Soon".to_string()));
+        assert!(!res.is_final);
+
+        // Permit signed for another poll is rejected
+        let msg = QueryMsg::PollResultsWithPermit {
+            poll_id: "001".parse().unwrap(),
+            permit: ResultsPermit {
+                pubkey,
+                signature: sign("002"),
+            },
+        };
+        let _err = query(deps.as_ref(), env.clone(), msg).unwrap_err();
+
+        // Once closed the results are public
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(creator.as_str(), &[]),
+            msg,
+        )
+        .unwrap();
+        let msg = QueryMsg::PollResults {
+            poll_id: "001".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.total_votes, Uint128::one());
+        let bin = query(deps.as_ref(), env, votes_by_address).unwrap();
+        let res: VotesByAddressResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.votes.len(), 1);
+    }
+
+    #[test]
+    fn test_query_poll() {
+        // Mock environment
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Poll 001
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Query for the poll that exists
+        let msg = QueryMsg::Poll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        // Assert exists
+        assert!(res.poll.is_some());
+
+        // Query non existing poll
+        let msg = QueryMsg::Poll {
+            poll_id: "none_id".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        // Assert none poll with that id
+        assert!(res.poll.is_none());
+
+        // Same answers without loading the polls
+        for (poll_id, exists) in [("001", true), ("none_id", false)] {
+            let msg = QueryMsg::PollExists {
+                poll_id: poll_id.parse().unwrap(),
+            };
+            let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+            let res: PollExistsResponse = from_binary(&bin).unwrap();
+            assert_eq!(res.exists, exists);
+        }
+    }
+
+    // Messages as sent by the first releases, they must still parse
+    #[test]
+    fn test_legacy_messages() {
+        let msg: InstantiateMsg = from_slice(br#"{"admin":null}"#).unwrap();
+        assert_eq!(msg.max_options, None);
+
+        let msg: ExecuteMsg = from_slice(
+            br#"{"create_poll":{"poll_id":"001","question":"Wen moon?","options":["Now","Soon"]}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            msg,
+            ExecuteMsg::CreatePoll {
+                slug: Some("001".parse().unwrap()),
+                question: "Wen moon?".to_string(),
+                options: vec!["Now".to_string(), "Soon".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            }
+        );
+        let msg: ExecuteMsg = from_slice(br#"{"vote":{"poll_id":"001","vote":"Now"}}"#).unwrap();
+        assert_eq!(
+            msg,
+            ExecuteMsg::Vote {
+                poll_id: "001".parse().unwrap(),
+                vote: "Now".to_string(),
+                on_behalf_of: None,
+            }
+        );
+        let msg: ExecuteMsg = from_slice(br#"{"update_config":{"max_options":8}}"#).unwrap();
+        assert!(matches!(
+            msg,
+            ExecuteMsg::UpdateConfig(update)
+                if update.max_options == Some(8) && update.min_options.is_none()
+        ));
+
+        let msg: QueryMsg = from_slice(br#"{"all_polls":{}}"#).unwrap();
+        assert_eq!(
+            msg,
+            QueryMsg::AllPolls {
+                start_after: None,
+                limit: None,
+                order: None,
+            }
+        );
+        let msg: QueryMsg = from_slice(br#"{"poll":{"poll_id":"001"}}"#).unwrap();
+        assert_eq!(
+            msg,
+            QueryMsg::Poll {
+                poll_id: "001".parse().unwrap(),
+            }
+        );
+        let msg: QueryMsg = from_slice(br#"{"vote":{"poll_id":"001","address":"addr1"}}"#).unwrap();
+        // Serializing and parsing again gives the same message
+        assert_eq!(from_slice::<QueryMsg>(&to_vec(&msg).unwrap()).unwrap(), msg);
+    }
+
+    // Indexers rely on these, changing a type or key breaks them
+    #[test]
+    fn test_event_schema() {
+        let keys = |event: &Event| -> Vec<String> {
+            event.attributes.iter().map(|a| a.key.clone()).collect()
+        };
+        let voter = Addr::unchecked(ADDR1);
+        let poll = Poll {
+            creator: voter.clone(),
+            question: "Wen moon?".to_string(),
+            options: vec![PollOption {
+                id: 0,
+                label: "Now".to_string(),
+            }],
+            status: PollStatus::Open,
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            total_votes: 0,
+            voter_count: 0,
+            created_at: Default::default(),
+            created_height: 0,
+            tags: vec![],
+            hide_results: false,
+            lazy_tally: false,
+            vote_token: None,
+            tie_break: TieBreak::NoWinner,
+            tie_winner: None,
+            dao: None,
+            slug: None,
+        };
+
+        let event = evt_poll_created("001", &poll);
+        assert_eq!(event.ty, "poll_created");
+        assert_eq!(
+            keys(&event),
+            ["poll_id", "creator", "options", "expires_at"]
+        );
+        let event = evt_vote("001", &voter, "Now", Some("Soon"));
+        assert_eq!(event.ty, "poll_voted");
+        assert_eq!(
+            keys(&event),
+            ["poll_id", "voter", "option", "previous_option"]
+        );
+        let event = evt_ballot_cast("001", &voter, "Now");
+        assert_eq!(event.ty, "ballot_cast");
+        assert_eq!(keys(&event), ["option", "poll_id", "voter"]);
+        let event = evt_ballot_changed("001", &voter, "Soon", "Now");
+        assert_eq!(event.ty, "ballot_changed");
+        assert_eq!(
+            keys(&event),
+            ["old_option", "new_option", "poll_id", "voter"]
+        );
+        let event = evt_poll_closed("001", &voter, None, Uint128::zero(), 0);
+        assert_eq!(event.ty, "poll_closed");
+        assert_eq!(
+            keys(&event),
+            ["poll_id", "closed_by", "winner", "winner_votes", "turnout"]
+        );
+    }
+
+    #[test]
+    fn test_migrate() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("001".parse().unwrap()),
+            question: "Wen moon?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Soon".to_string(),
+            on_behalf_of: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
+        // Stored the way it was before PollOption and voter_count
+        let key = POLLS.key("1");
+        let legacy = String::from_utf8(deps.storage.get(&key).unwrap())
+            .unwrap()
+            .replace(
+                r#"[{"id":0,"label":"Now"},{"id":1,"label":"Soon"},{"id":2,"label":"Never"}]"#,
+                r#"["Now","Soon","Never"]"#,
+            )
+            .replace("voter_count", "unique_voters");
+        deps.storage.set(&key, legacy.as_bytes());
+
+        // Still readable, the options get their position as id
+        let msg = QueryMsg::Poll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg.clone()).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
         assert_eq!(
-            res.attributes,
-            vec![attr("action", "instantiate"), attr("admin", ADDR1)]
-        )
+            poll.options[2],
+            PollOption {
+                id: 2,
+                label: "Never".to_string()
+            }
+        );
+        assert_eq!(poll.voter_count, 1);
+        // Ballots stored before their weight count one vote
+        deps.storage.set(
+            &STORED_BALLOTS.key((&Addr::unchecked(ADDR1), "1")),
+            br#"{"option":"Soon"}"#,
+        );
+        let vote_msg = QueryMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            address: ADDR1.to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), vote_msg).unwrap();
+        let vote = from_binary::<VoteResponse>(&bin).unwrap().vote.unwrap();
+        assert_eq!(vote.weight, Uint128::one());
+
+        // Stored with the first layout, votes were kept in the options
+        deps.storage.set(
+            &POLLS.key("002"),
+            br#"{"creator":"addr1","question":"Old?","options":[["Yes",3],["No",1]]}"#,
+        );
+        let old_msg = QueryMsg::Poll {
+            poll_id: "002".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), old_msg).unwrap();
+        let old_poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        assert_eq!(old_poll.status, PollStatus::Open);
+        assert_eq!(old_poll.options[1].label, "No");
+
+        let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+        assert_eq!(res.attributes[1], ("polls", "2"));
+        let results_msg = QueryMsg::PollResults {
+            poll_id: "002".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), results_msg).unwrap();
+        let res: PollResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.total_votes, Uint128::new(4));
+        assert_eq!(res.winner, Some("Yes".to_string()));
+        let stored = String::from_utf8(deps.storage.get(&key).unwrap()).unwrap();
+        assert!(stored.contains(r#"{"id":2,"label":"Never"}"#));
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        assert_eq!(from_binary::<PollResponse>(&bin).unwrap().poll, Some(poll));
+        // Nothing left in an older layout
+        let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+        assert_eq!(res.attributes[1], ("polls", "0"));
+
+        // Neither another contract nor a newer version
+        set_contract_version(deps.as_mut().storage, "crates.io:other", "0.1.0").unwrap();
+        let err = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap_err();
+        assert!(
+            matches!(err, ContractError::WrongContract { contract } if contract == "crates.io:other")
+        );
+        set_contract_version(deps.as_mut().storage, "crates.io:cw-starter", "99.0.0").unwrap();
+        let err = migrate(deps.as_mut(), env, MigrateMsg {}).unwrap_err();
+        assert!(matches!(err, ContractError::MigrationDowngrade { .. }));
     }
 
     #[test]
-    fn test_instantiate_with_admin() {
-        // Copy paste of test_instantiate but changes None to Some(ADDR2.to_string())
+    fn test_migrate_first_layout() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let info = mock_info(ADDR2, &[]);
+        // Storage of a contract instantiated and used with the first layout
+        set_contract_version(deps.as_mut().storage, "crates.io:cw-starter", "0.1.0").unwrap();
+        deps.storage.set(b"config", br#"{"admin":"addr1"}"#);
+        deps.storage.set(
+            &POLLS.key("001"),
+            br#"{"creator":"addr2","question":"Wen moon?","options":[["Now",2],["Soon",1]]}"#,
+        );
+        for (voter, option) in [(ADDR1, "Now"), (ADDR2, "Now"), ("addr3", "Soon")] {
+            deps.storage.set(
+                &STORED_BALLOTS.key((&Addr::unchecked(voter), "001")),
+                format!(r#"{{"option":"{option}"}}"#).as_bytes(),
+            );
+        }
 
-        let msg = InstantiateMsg {
-            admin: Some(ADDR2.to_string()),
+        let _res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!((config.min_options, config.max_options), (2, 5));
+        let ownership = cw_ownable::get_ownership(&deps.storage).unwrap();
+        assert_eq!(ownership.owner, Some(Addr::unchecked(ADDR1)));
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::Stats {}).unwrap();
+        let stats: Stats = from_binary(&bin).unwrap();
+        assert_eq!(
+            stats,
+            Stats {
+                total_polls: 1,
+                total_ballots: 3,
+                unique_voters: 3,
+            }
+        );
+        let msg = QueryMsg::Poll {
+            poll_id: "001".parse().unwrap(),
         };
-        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
-
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        assert_eq!((poll.voter_count, poll.total_votes), (3, 3));
+        let msg = QueryMsg::VotersByOption {
+            poll_id: "001".parse().unwrap(),
+            option: "Now".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: VotersByOptionResponse = from_binary(&bin).unwrap();
         assert_eq!(
-            res.attributes,
-            vec![attr("action", "instantiate"), attr("admin", ADDR2)]
-        )
-    }
+            res.voters,
+            vec![Addr::unchecked(ADDR1), Addr::unchecked(ADDR2)]
+        );
 
-    // Start of new tests
+        // Old ballots change like new ones, the admin owns the contract
+        let msg = ExecuteMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            vote: "Soon".to_string(),
+            on_behalf_of: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR1, &[]), msg).unwrap();
+        let msg = QueryMsg::PollResults {
+            poll_id: "001".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResultsResponse = from_binary(&bin).unwrap();
+        let votes: Vec<_> = res
+            .options
+            .iter()
+            .map(|option| option.votes.u128())
+            .collect();
+        assert_eq!(votes, vec![1, 2]);
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "001".parse().unwrap(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR1, &[]), msg).unwrap();
+        let msg = ExecuteMsg::CreatePoll {
+            slug: None,
+            question: "Wen lambo?".to_string(),
+            options: vec!["Now".to_string(), "Soon".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let res = execute(deps.as_mut(), env, mock_info(ADDR2, &[]), msg).unwrap();
+        assert!(res.attributes.contains(&attr("poll_id", "1")));
+    }
 
-    // Poll valid tests
     #[test]
-    fn test_execute_create_poll_valid() {
-        // The Mock values like in instantiate
+    fn test_query_poll_results() {
         let mut deps = mock_dependencies();
         let env = mock_env();
         let info = mock_info(ADDR1, &[]);
-        // Instantiate the contract
-        let msg = InstantiateMsg { admin: None };
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-
-        // New execute msg
         let msg = ExecuteMsg::CreatePoll {
-            poll_id: "Web3Builders".to_string(),
-            question: "Will I finish this on time?".to_string(),
-            options: vec![
-                "Yes".to_string(),
-                "No".to_string(),
-                "The world will end before that".to_string(),
-            ],
+            slug: Some("some_id".parse().unwrap()),
+            question: "Yes or no?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string(), "Maybe".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
         };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
-        // Unwrap to assert success
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        for (voter, vote) in [(ADDR1, "Yes"), (ADDR2, "No"), ("addr3", "Yes")] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: "some_id".parse().unwrap(),
+                vote: vote.to_string(),
+                on_behalf_of: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        let msg = QueryMsg::PollResults {
+            poll_id: "some_id".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg.clone()).unwrap();
+        let res: PollResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.total_votes, Uint128::new(3));
+        let poll_msg = QueryMsg::Poll {
+            poll_id: "some_id".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), poll_msg.clone()).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        assert_eq!((poll.total_votes, poll.voter_count), (3, 3));
+        assert_eq!(
+            res.options[0],
+            OptionResult {
+                id: 0,
+                option: "Yes".to_string(),
+                votes: Uint128::new(2),
+                percentage: Decimal::from_ratio(200u128, 3u128),
+            }
+        );
+        assert_eq!(res.options[2].percentage, Decimal::zero());
+        assert_eq!(res.options[2].id, 2);
+        assert_eq!(res.winner, Some("Yes".to_string()));
+        assert!(!res.tie);
+        assert!(!res.is_final);
+        let winner_msg = QueryMsg::Winner {
+            poll_id: "some_id".parse().unwrap(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), winner_msg.clone()).unwrap();
+        let res: WinnerResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.winner, Some("Yes".to_string()));
+
+        // A changed vote makes a tie
+        let vote = ExecuteMsg::Vote {
+            poll_id: "some_id".parse().unwrap(),
+            vote: "Maybe".to_string(),
+            on_behalf_of: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("addr3", &[]), vote).unwrap();
+        // It counts as a vote, not as a voter
+        let bin = query(deps.as_ref(), env.clone(), poll_msg).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        assert_eq!((poll.total_votes, poll.voter_count), (4, 3));
+        let close = ExecuteMsg::ClosePoll {
+            poll_id: "some_id".parse().unwrap(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, close).unwrap();
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.winner, None);
+        assert!(res.tie);
+        assert!(res.is_final);
+        let bin = query(deps.as_ref(), env, winner_msg).unwrap();
+        let res: WinnerResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.winner, None);
     }
 
-    //Poll invalid tests
     #[test]
-    fn test_execute_create_poll_invalid() {
+    fn test_query_tally_at_height() {
         let mut deps = mock_dependencies();
-        let env = mock_env();
+        let mut env = mock_env();
         let info = mock_info(ADDR1, &[]);
-        // Instantiate the contract
-        let msg = InstantiateMsg { admin: None };
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-
+        let created_height = env.block.height;
         let msg = ExecuteMsg::CreatePoll {
-            poll_id: "001".to_string(),
-            question: "How many numbers fit in this poll?".to_string(),
-            options: vec![
-                "1".to_string(),
-                "2".to_string(),
-                "3".to_string(),
-                "4".to_string(),
-                "5".to_string(),
-                "6".to_string(),
-            ],
+            slug: Some("some_id".parse().unwrap()),
+            question: "Yes or no?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
         };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        // The result flips from Yes to No
+        for (voter, vote) in [(ADDR1, "Yes"), (ADDR2, "No"), ("addr3", "No")] {
+            env.block.height += 1;
+            let msg = ExecuteMsg::Vote {
+                poll_id: "some_id".parse().unwrap(),
+                vote: vote.to_string(),
+                on_behalf_of: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
 
-        let _err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        for (height, expected) in [
+            (created_height, None),
+            (created_height + 2, Some((1, 0))),
+            (created_height + 3, Some((1, 1))),
+            (created_height + 4, Some((1, 2))),
+        ] {
+            let msg = QueryMsg::TallyAtHeight {
+                poll_id: "some_id".parse().unwrap(),
+                height,
+            };
+            let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+            let res: TallyAtHeightResponse = from_binary(&bin).unwrap();
+            let tallies = res
+                .options
+                .map(|options| (options[0].1.u128(), options[1].1.u128()));
+            assert_eq!(tallies, expected);
+        }
     }
 
-    // Vote valid
     #[test]
-    fn test_execute_vote_valid() {
-        // Envorinment
+    fn test_query_option_tally() {
         let mut deps = mock_dependencies();
         let env = mock_env();
         let info = mock_info(ADDR1, &[]);
-
-        // Instantiate the contract
-        let msg = InstantiateMsg { admin: None };
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-
-        // Poll created
         let msg = ExecuteMsg::CreatePoll {
-            poll_id: "000".to_string(),
-            question: "Choose an option".to_string(),
-            options: vec!["1".to_string(), "2".to_string(), "3".to_string()],
+            slug: Some("some_id".parse().unwrap()),
+            question: "Yes or no?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
         };
         let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-
-        // Vote executed
         let msg = ExecuteMsg::Vote {
-            poll_id: "000".to_string(),
-            vote: "1".to_string(),
+            poll_id: "some_id".parse().unwrap(),
+            vote: "Yes".to_string(),
+            on_behalf_of: None,
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        // Update vote
-        let msg = ExecuteMsg::Vote {
-            poll_id: "000".to_string(),
-            vote: "2".to_string(),
+        let msg = QueryMsg::OptionTally {
+            poll_id: "some_id".parse().unwrap(),
+            option: "Yes".to_string(),
         };
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: OptionTallyResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.votes, Uint128::one());
+
+        let msg = QueryMsg::OptionTally {
+            poll_id: "some_id".parse().unwrap(),
+            option: "Maybe".to_string(),
+        };
+        query(deps.as_ref(), env, msg).unwrap_err();
     }
 
-    // Vote invalid section
     #[test]
-    fn test_execute_vote_invalid() {
+    fn test_query_poll_voters() {
         let mut deps = mock_dependencies();
         let env = mock_env();
         let info = mock_info(ADDR1, &[]);
-        // Instantiate the contract
-        let msg = InstantiateMsg { admin: None };
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::CreatePoll {
+            slug: Some("some_id".parse().unwrap()),
+            question: "Yes or no?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        for (voter, vote) in [(ADDR1, "Yes"), (ADDR2, "No"), ("addr3", "Yes")] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: "some_id".parse().unwrap(),
+                vote: vote.to_string(),
+                on_behalf_of: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
 
-        // Vote created, poll doesn't exist.
-        let msg = ExecuteMsg::Vote {
-            poll_id: "000".to_string(),
-            vote: "Okonomiyaki".to_string(),
+        let msg = QueryMsg::PollVoters {
+            poll_id: "some_id".parse().unwrap(),
+            start_after: Some(ADDR1.to_string()),
+            limit: Some(1),
         };
-        // Unwrap to assert error
-        let _err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: PollVotersResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.voters,
+            vec![(Addr::unchecked(ADDR2), "No".to_string(), Uint128::one())]
+        );
+    }
 
-        // Poll created
+    #[test]
+    fn test_query_voters_by_option() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
         let msg = ExecuteMsg::CreatePoll {
-            poll_id: "000".to_string(),
-            question: "Favorite Japanese food".to_string(),
-            options: vec![
-                "Onigiri".to_string(),
-                "Okonomiyaki".to_string(),
-                "Ozoni".to_string(),
-            ],
+            slug: Some("some_id".parse().unwrap()),
+            question: "Yes or no?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        // addr3 changes its vote
+        for (voter, vote) in [
+            (ADDR1, "Yes"),
+            (ADDR2, "No"),
+            ("addr3", "Yes"),
+            ("addr3", "No"),
+        ] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: "some_id".parse().unwrap(),
+                vote: vote.to_string(),
+                on_behalf_of: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
 
-        // Poll created, Vote done, option
-        let msg = ExecuteMsg::Vote {
-            poll_id: "000".to_string(),
-            vote: "Pizza".to_string(),
+        for (option, expected) in [("Yes", vec![ADDR1]), ("No", vec![ADDR2, "addr3"])] {
+            let msg = QueryMsg::VotersByOption {
+                poll_id: "some_id".parse().unwrap(),
+                option: option.to_string(),
+                start_after: None,
+                limit: None,
+            };
+            let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+            let res: VotersByOptionResponse = from_binary(&bin).unwrap();
+            assert_eq!(res.voters, expected);
+        }
+
+        let msg = QueryMsg::VotersByOption {
+            poll_id: "some_id".parse().unwrap(),
+            option: "No".to_string(),
+            start_after: Some(ADDR2.to_string()),
+            limit: Some(1),
         };
-        let _err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: VotersByOptionResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.voters, vec![Addr::unchecked("addr3")]);
     }
 
     #[test]
-    fn test_query_all_polls() {
-        // Mock environment
+    fn test_query_votes_by_address() {
         let mut deps = mock_dependencies();
         let env = mock_env();
         let info = mock_info(ADDR1, &[]);
-        // Instantiate the contract
-        let msg = InstantiateMsg { admin: None };
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for poll_id in ["001", "002", "003"] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Yes or no?".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        for (voter, poll_id) in [(ADDR1, "001"), (ADDR2, "002"), (ADDR1, "003")] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: poll_id.parse().unwrap(),
+                vote: "Yes".to_string(),
+                on_behalf_of: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
 
-        // Poll 001
-        let msg = ExecuteMsg::CreatePoll {
-            poll_id: "001".to_string(),
-            question: "Wen moon?".to_string(),
-            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+        let msg = QueryMsg::VotesByAddress {
+            address: ADDR1.to_string(),
+            start_after: None,
+            limit: None,
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: VotesByAddressResponse = from_binary(&bin).unwrap();
+        let poll_ids: Vec<_> = res
+            .votes
+            .iter()
+            .map(|(poll_id, _)| poll_id.as_str())
+            .collect();
+        assert_eq!(poll_ids, vec!["1", "3"]);
 
-        // Poll 002
-        let msg = ExecuteMsg::CreatePoll {
-            poll_id: "002".to_string(),
-            question: "rgb?".to_string(),
-            options: vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()],
+        let msg = QueryMsg::VotesByAddress {
+            address: ADDR1.to_string(),
+            start_after: Some("1".to_string()),
+            limit: Some(1),
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: VotesByAddressResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.votes.len(), 1);
+        assert_eq!(res.votes[0].0, "3");
+    }
 
-        // Poll 003
-        let msg = ExecuteMsg::CreatePoll {
-            poll_id: "003".to_string(),
-            question: "another poll?".to_string(),
-            options: vec!["Yes".to_string(), "No".to_string()],
+    #[test]
+    fn test_query_stats() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
         };
-        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for poll_id in ["001", "002"] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Yes or no?".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        // The changed vote of ADDR1 in 001 doesn't count twice
+        for (voter, poll_id, vote) in [
+            (ADDR1, "001", "Yes"),
+            (ADDR1, "001", "No"),
+            (ADDR1, "002", "Yes"),
+            (ADDR2, "002", "No"),
+        ] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: poll_id.parse().unwrap(),
+                vote: vote.to_string(),
+                on_behalf_of: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
 
-        // Query process
-        let msg = QueryMsg::AllPolls {};
-        let bin = query(deps.as_ref(), env, msg).unwrap(); // Queries cannot change the state of a contract, so as_ref instead of as_mut
-        let res: AllPollsResponse = from_binary(&bin).unwrap();
-        assert_eq!(res.polls.len(), 3);
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::Stats {}).unwrap();
+        let res: Stats = from_binary(&bin).unwrap();
+        assert_eq!(
+            res,
+            Stats {
+                total_polls: 2,
+                total_ballots: 3,
+                unique_voters: 2,
+            }
+        );
+        // Counters stored under the former name
+        deps.storage.set(
+            b"stats",
+            br#"{"total_polls":2,"total_votes":3,"unique_voters":2}"#,
+        );
+        let bin = query(deps.as_ref(), env, QueryMsg::Stats {}).unwrap();
+        assert_eq!(from_binary::<Stats>(&bin).unwrap(), res);
     }
 
     #[test]
-    fn test_query_poll() {
-        // Mock environment
+    fn test_query_top_voters() {
         let mut deps = mock_dependencies();
         let env = mock_env();
         let info = mock_info(ADDR1, &[]);
-        // Instantiate the contract
-        let msg = InstantiateMsg { admin: None };
-        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-
-        // Poll 001
-        let msg = ExecuteMsg::CreatePoll {
-            poll_id: "001".to_string(),
-            question: "Wen moon?".to_string(),
-            options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
         };
-        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for poll_id in ["001", "002", "003"] {
+            let msg = ExecuteMsg::CreatePoll {
+                slug: Some(poll_id.parse().unwrap()),
+                question: "Yes or no?".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+                veto: None,
+                vote_deposit: None,
+                expires_at: None,
+                tags: None,
+                hide_results: None,
+                lazy_tally: None,
+                vote_token: None,
+                tie_break: None,
+                actions: None,
+                finalize_fee: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        // Changing a vote doesn't count as participating again
+        for (voter, poll_id, vote) in [
+            (ADDR2, "001", "Yes"),
+            (ADDR2, "002", "Yes"),
+            (ADDR2, "003", "Yes"),
+            (ADDR1, "001", "Yes"),
+            (ADDR1, "001", "No"),
+            ("addr3", "001", "No"),
+            ("addr3", "002", "No"),
+        ] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: poll_id.parse().unwrap(),
+                vote: vote.to_string(),
+                on_behalf_of: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
 
-        // Query for the poll that exists
-        let msg = QueryMsg::Poll {
-            poll_id: "001".to_string(),
+        // Changing a vote doesn't count twice
+        let msg = QueryMsg::Participation {
+            address: ADDR1.to_string(),
         };
         let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
-        let res: PollResponse = from_binary(&bin).unwrap();
-        // Assert exists
-        assert!(res.poll.is_some());
+        let res: ParticipationResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls, 1);
 
-        // Query non existing poll
-        let msg = QueryMsg::Poll {
-            poll_id: "none_id".to_string(),
-        };
+        let msg = QueryMsg::TopVoters { limit: Some(2) };
         let bin = query(deps.as_ref(), env, msg).unwrap();
-        let res: PollResponse = from_binary(&bin).unwrap();
-        // Assert none poll with that id
-        assert!(res.poll.is_none());
+        let res: TopVotersResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.voters,
+            vec![(Addr::unchecked(ADDR2), 3), (Addr::unchecked("addr3"), 2)]
+        );
     }
 
     #[test]
@@ -427,43 +10733,144 @@ mod tests {
         let env = mock_env();
         let info = mock_info(ADDR1, &[]);
         // Instantiate the contract
-        let msg = InstantiateMsg { admin: None };
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
         // Poll 001
         let msg = ExecuteMsg::CreatePoll {
-            poll_id: "001".to_string(),
+            slug: Some("001".parse().unwrap()),
             question: "Wen moon?".to_string(),
             options: vec!["Now".to_string(), "Soon".to_string(), "Never".to_string()],
+            veto: None,
+            vote_deposit: None,
+            expires_at: None,
+            tags: None,
+            hide_results: None,
+            lazy_tally: None,
+            vote_token: None,
+            tie_break: None,
+            actions: None,
+            finalize_fee: None,
         };
         let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
         // Vote
         let msg = ExecuteMsg::Vote {
-            poll_id: "001".to_string(),
+            poll_id: "001".parse().unwrap(),
             vote: "Now".to_string(),
+            on_behalf_of: None,
         };
         let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
         // Query existing vote
         let msg = QueryMsg::Vote {
-            poll_id: "001".to_string(),
+            poll_id: "001".parse().unwrap(),
             address: ADDR1.to_string(),
         };
         let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
         let res: VoteResponse = from_binary(&bin).unwrap();
-        // Expect exist
-        assert!(res.vote.is_some());
+        // Expect exist, with the counted weight
+        let vote = res.vote.unwrap();
+        assert_eq!(vote.option, "Now");
+        assert_eq!(vote.weight, Uint128::one());
 
         // Query non existing
         let msg = QueryMsg::Vote {
-            poll_id: "002".to_string(),
+            poll_id: "002".parse().unwrap(),
             address: ADDR2.to_string(),
         };
-        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
         let res: VoteResponse = from_binary(&bin).unwrap();
         // Expect none
         assert!(res.vote.is_none());
+
+        // Malformed addresses return an error instead of aborting
+        let msg = QueryMsg::Vote {
+            poll_id: "001".parse().unwrap(),
+            address: "Not An Address".to_string(),
+        };
+        let _err = query(deps.as_ref(), env, msg).unwrap_err();
+    }
+
+    #[test]
+    fn test_query_config() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: Some(ADDR2.to_string()),
+            vote_cooldown: Some(60),
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let bin = query(deps.as_ref(), env, QueryMsg::Config {}).unwrap();
+        let res: ConfigResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.owner, Some(Addr::unchecked(ADDR2)));
+        assert_eq!(res.config.vote_cooldown, Some(60));
+        assert_eq!(res.config.max_options, 5);
+        assert_eq!(res.contract_name, "crates.io:cw-starter");
+        assert_eq!(res.contract_version, env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn test_query_contract_info() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: Some(60),
+            creation_fee: Some(coin(10, "ujuno")),
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let bin = query(deps.as_ref(), env, QueryMsg::ContractInfo {}).unwrap();
+        let res: ContractInfoResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res,
+            ContractInfoResponse {
+                contract_name: "crates.io:cw-starter".to_string(),
+                contract_version: env!("CARGO_PKG_VERSION").to_string(),
+                admin: Some(Addr::unchecked(ADDR1)),
+                features: vec![
+                    "public_creation".to_string(),
+                    "vote_cooldown".to_string(),
+                    "creation_fee".to_string(),
+                ],
+            }
+        );
     }
 
     #[test]
@@ -473,10 +10880,27 @@ mod tests {
         let env = mock_env();
         let info = mock_info(ADDR1, &[]);
         // Instantiate the contract
-        let msg = InstantiateMsg { admin: None };
+        let msg = InstantiateMsg {
+            admin: None,
+            vote_cooldown: None,
+            creation_fee: None,
+            cw20_creation_fee: None,
+            creation_deposit: None,
+            deposit_slash_ratio: None,
+            flag_threshold: None,
+            max_open_polls_per_creator: None,
+            min_options: None,
+            max_options: None,
+            max_question_length: None,
+            max_option_length: None,
+        };
         let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        let msg = QueryMsg::AllPolls {};
+        let msg = QueryMsg::AllPolls {
+            start_after: None,
+            limit: None,
+            order: None,
+        };
         let bin = query(deps.as_ref(), env, msg).unwrap();
         let res: AllPollsResponse = from_binary(&bin).unwrap();
         assert_eq!(res.polls.len(), 0);
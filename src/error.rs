@@ -1,17 +1,201 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Coin, StdError, Timestamp, Uint128};
+use cw_ownable::OwnershipError;
+use cw_utils::PaymentError;
 use thiserror::Error;
 
+// The [Exxx] codes are stable for frontends: never renumber or reuse one,
+// new variants take the next free code
 #[derive(Error, Debug)]
 pub enum ContractError {
-    #[error("{0}")]
+    #[error("[E001] {0}")]
     Std(#[from] StdError),
 
-    #[error("Unauthorized")]
-    Unauthorized {},
+    #[error("[E002] {0}")]
+    Ownership(#[from] OwnershipError),
 
-    #[error("Too many poll options")]
-    TooManyOptions {},
+    #[error("[E003] {0}")]
+    Payment(#[from] PaymentError),
 
-    #[error("Poll not found")]
-    PollNotFound {},
+    #[error("[E004] Address {sender} is not authorized")]
+    Unauthorized { sender: String },
+
+    #[error("[E005] Address {sender} is not the creator of poll {poll_id}")]
+    NotPollCreator { sender: String, poll_id: String },
+
+    #[error("[E006] Address has no role")]
+    NoRole {},
+
+    #[error("[E007] Contract is paused")]
+    ContractPaused {},
+
+    #[error("[E008] Address {address} is banned")]
+    Banned { address: String },
+
+    #[error("[E009] Creator already has the maximum of {max} open polls")]
+    TooManyOpenPolls { max: u32 },
+
+    #[error("[E010] This action does not accept funds")]
+    UnexpectedFunds {},
+
+    #[error("[E011] Too many poll options, at most {max} allowed")]
+    TooManyOptions { max: u32 },
+
+    #[error("[E012] Too many tags, at most {max} allowed")]
+    TooManyTags { max: usize },
+
+    #[error("[E013] Too few poll options, at least {min} required")]
+    TooFewOptions { min: u32 },
+
+    #[error("[E014] Poll option {option} appears more than once")]
+    DuplicateOption { option: String },
+
+    #[error("[E015] Question too long, at most {max} bytes allowed")]
+    QuestionTooLong { max: u32 },
+
+    #[error("[E016] Poll option {option} too long, at most {max} bytes allowed")]
+    OptionTooLong { option: String, max: u32 },
+
+    #[error("[E017] Minimum options must be at least 1 and not above the maximum")]
+    InvalidOptionBounds {},
+
+    #[error("[E018] Poll {poll_id} expired")]
+    PollExpired { poll_id: String },
+
+    #[error("[E019] Expiration must be in the future")]
+    InvalidExpiration {},
+
+    #[error("[E020] Poll {poll_id} not found")]
+    PollNotFound { poll_id: String },
+
+    #[error("[E021] Poll {poll_id} has no option {option}")]
+    OptionNotFound { option: String, poll_id: String },
+
+    #[error("[E022] Poll id {poll_id} is already taken")]
+    PollIdTaken { poll_id: String },
+
+    #[error("[E023] Ballots can only be pruned once their poll is deleted")]
+    PollNotDeleted {},
+
+    #[error("[E024] Tally of {option} in poll {poll_id} doesn't match its ballots")]
+    TallyCorruption { poll_id: String, option: String },
+
+    #[error("[E025] Poll {poll_id} is closed")]
+    PollClosed { poll_id: String },
+
+    #[error("[E026] Poll {poll_id} is voted more than once in the batch")]
+    AlreadyVoted { poll_id: String },
+
+    #[error("[E027] Poll {poll_id} already removed")]
+    PollRemoved { poll_id: String },
+
+    #[error("[E028] Poll {poll_id} is paused pending review")]
+    PollPaused { poll_id: String },
+
+    #[error("[E029] Poll {poll_id} is not paused")]
+    PollNotPaused { poll_id: String },
+
+    #[error("[E030] Poll {poll_id} already flagged by {sender}")]
+    AlreadyFlagged { poll_id: String, sender: String },
+
+    #[error("[E031] Poll {poll_id} is still open")]
+    PollOpen { poll_id: String },
+
+    #[error("[E032] Vote deposit of {expected} required")]
+    DepositRequired { expected: Coin },
+
+    #[error("[E033] No deposit to claim in poll {poll_id}")]
+    NoDeposit { poll_id: String },
+
+    #[error("[E034] Vote cooldown, next vote allowed at {ready_at}")]
+    VoteCooldown { ready_at: Timestamp },
+
+    #[error("[E035] Poll creation fee of {expected} required")]
+    InsufficientFee { expected: Coin },
+
+    #[error("[E036] Poll creation fee of {expected} {token} required")]
+    InsufficientCw20Fee { token: String, expected: Uint128 },
+
+    #[error("[E037] Token {token} is not accepted")]
+    UnsupportedToken { token: String },
+
+    #[error("[E038] Creation fee and deposit must use the same denom")]
+    MismatchedDenoms {},
+
+    #[error("[E039] Deposit slash ratio cannot be above 1")]
+    InvalidSlashRatio {},
+
+    #[error("[E040] No fees to withdraw")]
+    NoFees {},
+
+    #[error("[E041] Invalid signature")]
+    InvalidSignature {},
+
+    #[error("[E042] Invalid public key")]
+    InvalidPubkey {},
+
+    #[error("[E043] Invalid nonce, expected {expected}")]
+    InvalidNonce { expected: u64 },
+
+    #[error("[E044] Polls voted with tokens can't require a vote deposit")]
+    VoteDepositWithToken {},
+
+    #[error("[E045] Poll {poll_id} is voted by sending {token}")]
+    TokenVoteRequired { poll_id: String, token: String },
+
+    #[error("[E046] This build doesn't support the tokenfactory module")]
+    TokenfactoryUnsupported {},
+
+    #[error("[E047] No reward to claim in the given polls")]
+    NoReward {},
+
+    #[error("[E048] Poll {poll_id} already has a lottery")]
+    LotteryExists { poll_id: String },
+
+    #[error("[E049] No pending randomness request {job_id}")]
+    UnknownRandomnessJob { job_id: String },
+
+    #[error("[E050] No randomness oracle configured")]
+    RandomnessUnavailable {},
+
+    #[error("[E051] Poll {poll_id} has no voters")]
+    NoVoters { poll_id: String },
+
+    #[error("[E052] A lottery needs at least one winner")]
+    InvalidWinners {},
+
+    #[error("[E053] Poll {poll_id} has not expired yet")]
+    PollNotExpired { poll_id: String },
+
+    #[error("[E054] Poll {poll_id} has no expiration")]
+    NoExpiration { poll_id: String },
+
+    #[error("[E055] No CronCat contract configured")]
+    SchedulerUnavailable {},
+
+    #[error("[E056] Poll {poll_id} already has a finalization task")]
+    FinalizeScheduled { poll_id: String },
+
+    #[error("[E057] Hook {hook} is already registered")]
+    HookAlreadyRegistered { hook: String },
+
+    #[error("[E058] Hook {hook} is not registered")]
+    HookNotRegistered { hook: String },
+
+    #[error("[E059] Threshold must not be above 1")]
+    InvalidThreshold {},
+
+    #[error("[E060] No voting power in the DAO of poll {poll_id}")]
+    NoVotingPower { poll_id: String },
+
+    #[error("[E061] Poll {poll_id} breaks ties at random, CronCat can't pay the randomness")]
+    RandomTieBreakScheduled { poll_id: String },
+
+    #[error("[E062] Slug {slug} is a poll number")]
+    NumericSlug { slug: String },
+
+    #[error("[E063] Can't migrate from contract {contract}")]
+    WrongContract { contract: String },
+
+    #[error("[E064] Can't migrate from version {stored} down to {current}")]
+    MigrationDowngrade { stored: String, current: String },
 }
@@ -0,0 +1,74 @@
+// Events emitted by the contract. Types and attribute keys are kept stable for
+// indexers, handlers build their events here only
+use cosmwasm_std::{Addr, Event, Uint128};
+
+use crate::state::Poll;
+
+pub const POLL_CREATED: &str = "poll_created";
+pub const POLL_VOTED: &str = "poll_voted";
+pub const BALLOT_CAST: &str = "ballot_cast";
+pub const BALLOT_CHANGED: &str = "ballot_changed";
+pub const POLL_CLOSED: &str = "poll_closed";
+
+// Expiration in seconds, none without one
+pub fn expiration_attribute(poll: &Poll) -> String {
+    poll.expires_at.map_or("none".to_string(), |expires_at| {
+        expires_at.seconds().to_string()
+    })
+}
+
+pub fn evt_poll_created(poll_id: &str, poll: &Poll) -> Event {
+    Event::new(POLL_CREATED)
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("creator", &poll.creator)
+        .add_attribute("options", poll.options.len().to_string())
+        .add_attribute("expires_at", expiration_attribute(poll))
+}
+
+// previous_option only for changed votes
+pub fn evt_vote(poll_id: &str, voter: &Addr, option: &str, previous: Option<&str>) -> Event {
+    let event = Event::new(POLL_VOTED)
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("voter", voter)
+        .add_attribute("option", option);
+    match previous {
+        Some(previous) => event.add_attribute("previous_option", previous),
+        None => event,
+    }
+}
+
+pub fn evt_ballot_cast(poll_id: &str, voter: &Addr, option: &str) -> Event {
+    Event::new(BALLOT_CAST)
+        .add_attribute("option", option)
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("voter", voter)
+}
+
+pub fn evt_ballot_changed(
+    poll_id: &str,
+    voter: &Addr,
+    old_option: &str,
+    new_option: &str,
+) -> Event {
+    Event::new(BALLOT_CHANGED)
+        .add_attribute("old_option", old_option)
+        .add_attribute("new_option", new_option)
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("voter", voter)
+}
+
+// winner is none without votes or on a tie
+pub fn evt_poll_closed(
+    poll_id: &str,
+    closed_by: &Addr,
+    winner: Option<&str>,
+    winner_votes: Uint128,
+    turnout: u64,
+) -> Event {
+    Event::new(POLL_CLOSED)
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("closed_by", closed_by)
+        .add_attribute("winner", winner.unwrap_or("none"))
+        .add_attribute("winner_votes", winner_votes)
+        .add_attribute("turnout", turnout.to_string())
+}
@@ -1,7 +1,10 @@
 pub mod contract;
 mod error;
+pub mod events;
 pub mod helpers;
 pub mod msg;
 pub mod state;
+#[cfg(feature = "tokenfactory")]
+mod tokenfactory;
 
 pub use crate::error::ContractError;
@@ -1,24 +1,197 @@
-use crate::state::{Ballot, Poll};
+use crate::state::{
+    AuditEntry, Ballot, Config, Lottery, Poll, PollActions, PollId, PollStatus, Removal,
+    RewardPool, Role, Stats, TieBreak,
+};
+use cosmwasm_schema::QueryResponses;
+use cosmwasm_std::{
+    Addr, Binary, Coin, CosmosMsg, Decimal, Empty, HexBinary, Order, Timestamp, Uint128,
+};
+use cw2::ContractVersion;
+use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ReceiveMsg};
+use cw_utils::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+// Messages of older frontends must keep parsing: fields added to existing
+// messages are Option (missing reads as None) and renamed variants or fields
+// keep their old name as a serde alias. test_legacy_messages holds them
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct InstantiateMsg {
-    pub admin: Option<String>,
+    pub admin: Option<String>,      // Gets the Owner role, sender by default
+    pub vote_cooldown: Option<u64>, // Seconds an address has to wait between vote actions
+    pub creation_fee: Option<Coin>, // Fee to pay for creating a poll
+    pub cw20_creation_fee: Option<Cw20Coin>, // Same fee in a cw20 token
+    pub creation_deposit: Option<Coin>, // Refundable deposit, same denom as the native fee
+    pub deposit_slash_ratio: Option<Decimal>, // Deposit share slashed on removal, all by default
+    pub flag_threshold: Option<u32>, // Flags pausing a poll, no automatic pause by default
+    pub max_open_polls_per_creator: Option<u32>, // No limit by default
+    pub min_options: Option<u32>,   // Options a poll needs at least, 2 by default
+    pub max_options: Option<u32>,   // Options a poll can have at most, 5 by default
+    pub max_question_length: Option<u32>, // Bytes of the question, 280 by default
+    pub max_option_length: Option<u32>, // Bytes of each option, 64 by default
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     CreatePoll {
-        poll_id: String,
+        #[serde(alias = "poll_id")]
+        slug: Option<PollId>, // Alias usable in place of the number the poll gets as id
         question: String,
         options: Vec<String>,
+        veto: Option<String>, // Address that can veto the poll, managers always can
+        vote_deposit: Option<Coin>, // Deposit required to vote, refunded after the poll closes
+        expires_at: Option<Timestamp>, // Votes are rejected from then on, no expiration by default
+        tags: Option<Vec<String>>, // Topics to find the poll by, at most 5
+        hide_results: Option<bool>, // Keeps the tallies private until the poll is finalized
+        lazy_tally: Option<bool>, // Counts the ballots once closed, cheaper votes without live results
+        vote_token: Option<String>, // cw20 voters send as vote weight, refunded once finalized
+        tie_break: Option<TieBreak>, // No winner on a tie by default
+        actions: Option<PollActions>, // Sent if the option passes once expired, owner only
+        finalize_fee: Option<Coin>, // Part of the funds paying a CronCat task finalizing the poll at expiration
     },
     Vote {
-        poll_id: String,
+        poll_id: PollId,
         vote: String,
+        on_behalf_of: Option<String>, // Voter that authorized the sender as its proxy
+    },
+    // Authorizes proxy to vote on behalf of the sender, None removes the current one
+    SetVoteProxy {
+        proxy: Option<String>,
+    },
+    // Votes in several polls at once, list of (poll_id, vote)
+    VoteMany {
+        votes: Vec<(PollId, String)>,
+    },
+    // Vote signed off-chain by the voter and submitted by a relayer paying the fees
+    VoteBySignature {
+        poll_id: PollId,
+        option: String,
+        voter_pubkey: Binary, // Compressed secp256k1 public key
+        signature: Binary,    // Signature of the ADR-036 sign doc of SignedVotePayload
+        nonce: u64,
+    },
+    // Finalizes the poll, only the creator or a manager
+    ClosePoll {
+        poll_id: PollId,
+    },
+    // Closes a poll once its expiration passed, anyone can
+    FinalizeExpired {
+        poll_id: PollId,
+    },
+    // Registers a CronCat task sending FinalizeExpired at the expiration of a
+    // poll created without finalize_fee. The sent funds pay the task
+    ScheduleFinalize {
+        poll_id: PollId,
+    },
+    // Marks an open poll as vetoed, only the veto address of the poll or a manager
+    VetoPoll {
+        poll_id: PollId,
+    },
+    // Refunds the creation deposit to the creator once the poll is finalized,
+    // minus what was slashed if moderation removed it
+    ReclaimDeposit {
+        poll_id: PollId,
+    },
+    // Refunds the vote deposit and the vote tokens of the sender once the poll is finalized
+    ClaimDeposit {
+        poll_id: PollId,
+    },
+    // Adds the sent coins to the reward pool of an open poll, anyone can sponsor
+    FundReward {
+        poll_id: PollId,
+    },
+    // Pays the sender its share of the reward pools of closed polls, by ballot
+    // weight. Sponsors get their part back from polls that ended otherwise or
+    // without votes
+    ClaimReward {
+        poll_ids: Vec<PollId>,
+    },
+    // Draws winners among the voters of a finalized poll with Nois randomness,
+    // paying prize to each. The sent funds hold the prizes, the rest pays the
+    // randomness fee. Creator or managers
+    StartLottery {
+        poll_id: PollId,
+        winners: u32,
+        prize: Coin,
+    },
+    // Randomness requested from the Nois proxy, only it can send this
+    NoisReceive {
+        callback: NoisCallback,
+    },
+    // Subscribes a contract to the PollHookMsg of every poll, only managers
+    AddHook {
+        addr: String,
+    },
+    RemoveHook {
+        addr: String,
+    },
+    // Hooks of a DAO DAO proposal module, only the DAO. Polls are the proposals
+    AddProposalHook {
+        address: String,
+    },
+    RemoveProposalHook {
+        address: String,
+    },
+    AddVoteHook {
+        address: String,
+    },
+    RemoveVoteHook {
+        address: String,
+    },
+    // The owner grants any role, managers only the moderator one
+    GrantRole {
+        address: String,
+        role: Role,
+    },
+    // Same rights as GrantRole, applied to the current role of address
+    RevokeRole {
+        address: String,
+    },
+    // Transfers, accepts or renounces the ownership of the contract
+    UpdateOwnership(cw_ownable::Action),
+    // Changes the operational parameters, only managers. None leaves a value unchanged
+    UpdateConfig(ConfigUpdate),
+    // Sending the cw20 creation fee with a ReceiveMsg
+    Receive(Cw20ReceiveMsg),
+    // Stops or resumes poll creation and voting, only managers
+    SetPaused {
+        paused: bool,
+    },
+    // Stops address from creating polls and voting, only moderators
+    BanAddress {
+        address: String,
+    },
+    UnbanAddress {
+        address: String,
+    },
+    // Reports a poll, enough distinct flags pause it
+    FlagPoll {
+        poll_id: PollId,
+        reason: String,
+    },
+    // Reopens a paused poll after review, clearing its flags, only moderators
+    ResumePoll {
+        poll_id: PollId,
+    },
+    // Deletes spam polls at once, their ballots are left to PruneBallots. Only managers
+    EmergencyRemove {
+        poll_ids: Vec<PollId>,
+    },
+    // Deletes up to limit ballots left by a deleted poll, anyone can call it
+    PruneBallots {
+        poll_id: PollId,
+        limit: Option<u32>,
+    },
+    // Takes down a poll keeping a public record of it, only moderators
+    RemovePoll {
+        poll_id: PollId,
+        reason: String,
+    },
+    // Sends the collected creation fees to the treasury, only the owner
+    WithdrawFees {
+        to: String,
     },
     // Can't stay available or msg doesn't work in contract.rs
     // DeletePoll{
@@ -32,24 +205,604 @@ pub enum ExecuteMsg {
     // },
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+// Parameters changed by UpdateConfig, sent as the fields of the message
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ConfigUpdate {
+    pub min_options: Option<u32>,
+    pub max_options: Option<u32>,
+    pub allow_public_creation: Option<bool>,
+    pub vote_cooldown: Option<u64>,          // 0 disables the cooldown
+    pub creation_fee: Option<Coin>,          // A zero amount removes the fee
+    pub cw20_creation_fee: Option<Cw20Coin>, // A zero amount removes the fee
+    pub creation_deposit: Option<Coin>,      // A zero amount removes the deposit
+    pub deposit_slash_ratio: Option<Decimal>,
+    pub flag_threshold: Option<u32>, // 0 disables the automatic pause
+    pub max_open_polls_per_creator: Option<u32>, // 0 removes the limit
+    pub max_question_length: Option<u32>,
+    pub max_option_length: Option<u32>,
+    pub debug_events: Option<bool>, // Tallies and counters on each vote, for troubleshooting
+    pub voted_nft: Option<String>,  // An empty address stops the minting
+    pub participation_denom: Option<String>, // An empty denom stops the minting
+    pub nois_proxy: Option<String>, // An empty address disables randomness
+    pub croncat_tasks: Option<String>, // An empty address disables scheduling
+    pub dao: Option<String>,        // An empty address detaches the contract from its DAO
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, QueryResponses)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    AllPolls {}, // Gets all the existing polls
+    #[returns(AllPollsResponse)]
+    AllPolls {
+        // Gets the existing polls, a page at a time
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order: Option<OrderBy>, // Ascending poll_id by default
+    },
+    #[returns(AllPollsResponse)]
+    PollsByCreator {
+        // Gets the polls of one creator, a page at a time
+        creator: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order: Option<OrderBy>, // Ascending poll_id by default
+    },
+    #[returns(AllPollsResponse)]
+    PollsByStatus {
+        // Gets the polls with one status, a page at a time
+        status: PollStatus,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order: Option<OrderBy>, // Ascending poll_id by default
+    },
+    #[returns(AllPollsResponse)]
+    PollsByCreatorAndStatus {
+        // Gets the polls of one creator with one status, a page at a time
+        creator: String,
+        status: PollStatus,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order: Option<OrderBy>, // Ascending poll_id by default
+    },
+    #[returns(AllPollsResponse)]
+    PollsEndingBefore {
+        // Gets the open polls expiring before timestamp, the soonest first
+        timestamp: Timestamp,
+        limit: Option<u32>,
+    },
+    #[returns(AllPollsResponse)]
+    RecentPolls {
+        // Gets the newest polls first
+        limit: Option<u32>,
+    },
+    #[returns(AllPollsResponse)]
+    PollsWithPrefix {
+        // Gets the polls whose slug starts with prefix, like a namespace
+        prefix: String,
+        limit: Option<u32>,
+    },
+    #[returns(AllPollsResponse)]
+    PollsById {
+        // Gets several polls at once, skipping the unknown ids
+        poll_ids: Vec<PollId>,
+    },
+    #[returns(AllPollsResponse)]
+    PollsByTag {
+        // Gets the polls with one tag, a page at a time
+        tag: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(AllPollsResponse)]
+    OpenPollsNotVoted {
+        // Gets the open polls address can still vote in, a page at a time
+        address: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(PollResponse)]
     Poll {
-        poll_id: String, // Gets one poll
+        poll_id: PollId, // Gets one poll
+    },
+    #[returns(PollMetadataResponse)]
+    PollMetadata {
+        poll_id: PollId, // Gets one poll without its options and tallies
+    },
+    #[returns(PollExistsResponse)]
+    PollExists {
+        poll_id: PollId, // Checks the id without loading the poll
+    },
+    #[returns(PollResultsResponse)]
+    PollResults {
+        // Gets the tallies, percentages and winner of one poll
+        poll_id: PollId,
+    },
+    // Same as PollResults, also while hidden for the creator or a manager signing the permit
+    #[returns(PollResultsResponse)]
+    PollResultsWithPermit {
+        poll_id: PollId,
+        permit: ResultsPermit,
+    },
+    #[returns(WinnerResponse)]
+    Winner {
+        // Gets only the leading option of a poll
+        poll_id: PollId,
+    },
+    #[returns(TallyAtHeightResponse)]
+    TallyAtHeight {
+        // Gets the votes of a poll as they were at the beginning of a past block
+        poll_id: PollId,
+        height: u64,
+    },
+    #[returns(OptionTallyResponse)]
+    OptionTally {
+        // Gets the votes of one option of a poll
+        poll_id: PollId,
+        option: String,
+    },
+    #[returns(PollVotersResponse)]
+    PollVoters {
+        // Gets the ballots of a poll, a page at a time in address order
+        poll_id: PollId,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(VotersByOptionResponse)]
+    VotersByOption {
+        // Gets the addresses that voted one option of a poll, a page at a time
+        poll_id: PollId,
+        option: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(VotesByAddressResponse)]
+    VotesByAddress {
+        // Gets the ballots of one address across polls, a page at a time,
+        // leaving out the polls hiding their results
+        address: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
     },
+    #[returns(ParticipationResponse)]
+    Participation {
+        // Gets the number of distinct polls the address voted in
+        address: String,
+    },
+    #[returns(TopVotersResponse)]
+    TopVoters {
+        // Gets the addresses that voted in the most polls
+        limit: Option<u32>,
+    },
+    #[returns(VoteResponse)]
     Vote {
         // Gets the vote of one address
+        poll_id: PollId,
+        address: String,
+    },
+    #[returns(VoteResponse)]
+    VoteAtHeight {
+        // Gets the vote of one address at the beginning of a past block, only
+        // for the blocks following a poll close
+        poll_id: PollId,
+        address: String,
+        height: u64,
+    },
+    #[returns(cw_ownable::Ownership<Addr>)]
+    Ownership {}, // Gets the owner and pending ownership transfer, cw_ownable::Ownership
+    #[returns(ConfigResponse)]
+    Config {}, // Gets the contract parameters and version
+    #[returns(ContractInfoResponse)]
+    ContractInfo {}, // Gets the release and admin of this deployment, for indexers
+    #[returns(Stats)]
+    Stats {}, // Gets the contract wide counters, state::Stats
+    #[returns(RemovalResponse)]
+    Removal {
+        // Gets who removed a poll and why
+        poll_id: PollId,
+    },
+    #[returns(RewardPool)]
+    RewardPool {
+        // Gets the rewards funded for the voters of a poll
+        poll_id: PollId,
+    },
+    #[returns(Lottery)]
+    Lottery {
+        // Gets the lottery of a poll and its winners once drawn
+        poll_id: PollId,
+    },
+    #[returns(HooksResponse)]
+    Hooks {}, // Gets the contracts subscribed to the polls
+    // Queries of a DAO DAO proposal module
+    #[returns(Addr)]
+    Dao {}, // Gets the DAO the contract is a module of
+    #[returns(InfoResponse)]
+    Info {}, // Gets the cw2 version
+    #[returns(u64)]
+    ProposalCount {}, // Gets the number of polls ever created
+    #[returns(u64)]
+    NextProposalId {}, // Gets the number the next auto numbered poll takes
+    #[returns(HooksResponse)]
+    ProposalHooks {},
+    #[returns(HooksResponse)]
+    VoteHooks {},
+    #[returns(Option<PollActions>)]
+    PollActions {
+        // Gets the messages a poll sends if its option passes
+        poll_id: PollId,
+    },
+    #[returns(PendingFeesResponse)]
+    PendingFees {}, // Gets the creation fees not withdrawn yet
+    #[returns(AuditLogResponse)]
+    AuditLog {
+        // Gets the admin and moderator actions, oldest first
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+// Mint message of cw721-base, the only cw721 message this contract sends
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw721ExecuteMsg {
+    Mint {
+        token_id: String,
+        owner: String,
+        token_uri: Option<String>,
+        extension: Option<Empty>,
+    },
+}
+
+// Notification sent to each hook on poll creation, votes and close
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PollHookMsg {
+    PollCreated {
         poll_id: String,
+        creator: String,
+    },
+    // First and changed ballots, previous_option only for changed ones
+    Voted {
+        poll_id: String,
+        voter: String,
+        option: String,
+        previous_option: Option<String>,
+    },
+    PollClosed {
+        poll_id: String,
+        winner: Option<String>,
+    },
+}
+
+// Execute message hook contracts have to accept
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PollHookExecuteMsg {
+    PollHook(PollHookMsg),
+}
+
+// Request of the Nois proxy, the only one this contract sends
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NoisProxyExecuteMsg {
+    GetNextRandomness { job_id: String },
+}
+
+// Randomness delivered by the Nois proxy with NoisReceive
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct NoisCallback {
+    pub job_id: String,
+    pub published: Timestamp,
+    pub randomness: HexBinary,
+}
+
+// Messages of the CronCat tasks contract this contract sends
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CroncatExecuteMsg {
+    CreateTask { task: Box<CroncatTaskRequest> },
+    RemoveTask { task_hash: String },
+}
+
+// Subset of the CronCat TaskRequest this contract fills
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct CroncatTaskRequest {
+    pub interval: CroncatInterval,
+    pub boundary: Option<CroncatBoundary>,
+    pub stop_on_fail: bool,
+    pub actions: Vec<CroncatAction>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CroncatInterval {
+    Once,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CroncatBoundary {
+    Time {
+        start: Option<Timestamp>,
+        end: Option<Timestamp>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct CroncatAction {
+    pub msg: CosmosMsg,
+    pub gas_limit: Option<u64>,
+}
+
+// Query of the DAO DAO core this contract sends, the core forwards it to its
+// voting module
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DaoQueryMsg {
+    VotingPowerAtHeight {
         address: String,
+        height: Option<u64>,
     },
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct VotingPowerAtHeightResponse {
+    pub power: Uint128,
+    pub height: u64,
+}
+
+// Hooks of DAO DAO proposal modules, ids being the numeric poll ids
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalHookMsg {
+    NewProposal {
+        id: u64,
+        proposer: String,
+    },
+    ProposalStatusChanged {
+        id: u64,
+        old_status: String,
+        new_status: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteHookMsg {
+    NewVote {
+        proposal_id: u64,
+        voter: String,
+        vote: String, // Option voted
+    },
+}
+
+// Execute message wrapping the DAO DAO hooks, as the hook contracts take them
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DaoHookExecuteMsg {
+    ProposalHook(ProposalHookMsg),
+    VoteHook(VoteHookMsg),
+}
+
+// Queries of the cw3 spec, answered with the polls as proposals so cw3 tooling
+// can read the contract. Proposal ids are the numeric poll ids
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, QueryResponses)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw3QueryMsg {
+    // Polls are won by plurality, there is no contract wide threshold
+    #[returns(Cw3ThresholdResponse)]
+    Threshold {},
+    #[returns(Cw3ProposalResponse)]
+    Proposal { proposal_id: u64 },
+    #[returns(Cw3ProposalListResponse)]
+    ListProposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(Cw3VoteResponse)]
+    Vote { proposal_id: u64, voter: String },
+    #[returns(Cw3VoteListResponse)]
+    ListVotes {
+        proposal_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+// Query of the entry point, the cw3 queries being the ones QueryMsg doesn't parse.
+// QueryMsg::Vote takes a poll_id, a cw3 vote query falls through to Cw3QueryMsg::Vote
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(untagged)]
+pub enum QueryEntryMsg {
+    Contract(QueryMsg),
+    Cw3(Cw3QueryMsg),
+}
+
+// cw3 Status
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw3Status {
+    Pending,
+    Open,
+    Rejected,
+    Passed,
+    Executed,
+}
+
+impl Cw3Status {
+    // Also the status names of the DAO DAO hooks
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Cw3Status::Pending => "pending",
+            Cw3Status::Open => "open",
+            Cw3Status::Rejected => "rejected",
+            Cw3Status::Passed => "passed",
+            Cw3Status::Executed => "executed",
+        }
+    }
+}
+
+// cw3 Vote
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw3Vote {
+    Yes,
+    No,
+    Abstain,
+    Veto,
+}
+
+// cw3 ThresholdResponse
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw3ThresholdResponse {
+    AbsoluteCount {
+        weight: u64,
+        total_weight: u64,
+    },
+    AbsolutePercentage {
+        percentage: Decimal,
+        total_weight: u64,
+    },
+    ThresholdQuorum {
+        threshold: Decimal,
+        quorum: Decimal,
+        total_weight: u64,
+    },
+}
+
+// cw3 ProposalResponse, polls never take a deposit in the cw3 sense
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw3ProposalResponse {
+    pub id: u64,
+    pub title: String,       // Question
+    pub description: String, // Options, comma separated
+    pub msgs: Vec<CosmosMsg>,
+    pub status: Cw3Status,
+    pub expires: Expiration,
+    pub threshold: Cw3ThresholdResponse,
+    pub proposer: Addr,
+    pub deposit: Option<Empty>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw3ProposalListResponse {
+    pub proposals: Vec<Cw3ProposalResponse>,
+}
+
+// cw3 VoteInfo
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Cw3VoteInfo {
+    pub proposal_id: u64,
+    pub voter: String,
+    pub vote: Cw3Vote,
+    pub weight: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Cw3VoteResponse {
+    pub vote: Option<Cw3VoteInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Cw3VoteListResponse {
+    pub votes: Vec<Cw3VoteInfo>,
+}
+
+// Sort order of listing queries, cosmwasm_std::Order can't be serialized
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderBy {
+    Ascending,
+    Descending,
+}
+
+impl From<OrderBy> for Order {
+    fn from(order: OrderBy) -> Order {
+        match order {
+            OrderBy::Ascending => Order::Ascending,
+            OrderBy::Descending => Order::Descending,
+        }
+    }
+}
+
+// Messages only the chain itself can send, through native governance
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SudoMsg {
+    // Same as ExecuteMsg::RemovePoll
+    RemovePoll { poll_id: PollId, reason: String },
+    // Replaces the owner, None leaves the contract without one
+    SetAdmin { admin: Option<String> },
+}
+
+// Messages embedded in the cw20 Send of ExecuteMsg::Receive
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    // Same as ExecuteMsg::CreatePoll, the sent tokens pay the creation fee
+    CreatePoll {
+        #[serde(alias = "poll_id")]
+        slug: Option<PollId>,
+        question: String,
+        options: Vec<String>,
+        veto: Option<String>,
+        vote_deposit: Option<Coin>,
+        expires_at: Option<Timestamp>,
+        tags: Option<Vec<String>>,
+        hide_results: Option<bool>,
+        lazy_tally: Option<bool>,
+        vote_token: Option<String>,
+        tie_break: Option<TieBreak>,
+        actions: Option<Box<PollActions>>,
+    },
+    // Votes in a poll of the sent token, adding the amount to the weight of the
+    // ballot. Tokens are escrowed until the poll is finalized
+    Vote {
+        poll_id: PollId,
+        option: String,
+    },
+    // Same as ExecuteMsg::FundReward with the sent tokens
+    FundReward {
+        poll_id: PollId,
+    },
+}
+
+// Data the voter signs for VoteBySignature, serialized as JSON in field order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SignedVotePayload {
+    pub contract: String,
+    pub poll_id: String,
+    pub option: String,
+    pub nonce: u64,
+}
+
+// Signature of the ADR-036 sign doc of ResultsPermitPayload
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ResultsPermit {
+    pub pubkey: Binary, // Compressed secp256k1 public key
+    pub signature: Binary,
+}
+
+// Data signed for PollResultsWithPermit, serialized as JSON in field order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ResultsPermitPayload {
+    pub contract: String,
+    pub poll_id: String,
+}
+
 // Structures created to respond to queried messages
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct AllPollsResponse {
-    pub polls: Vec<Poll>,
+    pub polls: Vec<(String, Poll)>,       // (poll_id, poll)
+    pub next_start_after: Option<String>, // start_after of the next page, None on the last one
+    pub total_count: Option<u64>,         // Set when known without iterating the whole list
+}
+
+// Data of the CreatePoll responses, for contracts creating polls in submessages
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct CreatePollResponse {
+    pub poll_id: String,
+    pub poll: Poll,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -57,11 +810,139 @@ pub struct PollResponse {
     pub poll: Option<Poll>,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct PollMetadataResponse {
+    pub metadata: Option<PollMetadata>,
+}
+
+// Descriptive fields of a Poll
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct PollMetadata {
+    pub creator: Addr,
+    pub question: String,
+    pub status: PollStatus,
+    pub tags: Vec<String>,
+    pub created_at: Timestamp,
+    pub expires_at: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct PollExistsResponse {
+    pub exists: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct PollResultsResponse {
+    pub total_votes: Uint128,
+    pub options: Vec<OptionResult>, // In the order of the poll options
+    pub winner: Option<String>,     // None without votes, on a tie or if vetoed or removed
+    pub tie: bool,
+    pub tie_break: TieBreak, // How the winner of a tie is chosen
+    pub is_final: bool,      // Closed, vetoed or removed, tallies won't change anymore
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct OptionResult {
+    pub id: u8,
+    pub option: String,
+    pub votes: Uint128,
+    pub percentage: Decimal, // Share of the total votes, from 0 to 100
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct WinnerResponse {
+    pub winner: Option<String>, // Same as PollResultsResponse::winner
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct HooksResponse {
+    pub hooks: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct TallyAtHeightResponse {
+    pub options: Option<Vec<(String, Uint128)>>, // None if the poll didn't exist yet
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct OptionTallyResponse {
+    pub votes: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct PollVotersResponse {
+    pub voters: Vec<(Addr, String, Uint128)>, // (address, option, weight)
+    pub next_start_after: Option<String>,     // start_after of the next page, None on the last one
+    pub total_count: Option<u64>,             // Set when known without iterating the whole list
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct VotersByOptionResponse {
+    pub voters: Vec<Addr>,
+    pub next_start_after: Option<String>, // start_after of the next page, None on the last one
+    pub total_count: Option<u64>,         // Set when known without iterating the whole list
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct VotesByAddressResponse {
+    pub votes: Vec<(String, Ballot)>,     // (poll_id, ballot)
+    pub next_start_after: Option<String>, // start_after of the next page, None on the last one
+    pub total_count: Option<u64>,         // Set when known without iterating the whole list
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct ParticipationResponse {
+    pub polls: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct TopVotersResponse {
+    pub voters: Vec<(Addr, u64)>, // (address, polls voted), the most active first
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct VoteResponse {
     pub vote: Option<Ballot>,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct RemovalResponse {
+    pub removal: Option<Removal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AuditLogResponse {
+    pub entries: Vec<(u64, AuditEntry)>, // By sequence number
+    pub next_start_after: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PendingFeesResponse {
+    pub fees: Vec<Coin>,
+    pub cw20_fees: Vec<Cw20CoinVerified>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ConfigResponse {
+    pub config: Config,
+    pub owner: Option<Addr>,
+    pub contract_name: String,    // From cw2
+    pub contract_version: String, // From cw2
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct ContractInfoResponse {
+    pub contract_name: String,    // From cw2
+    pub contract_version: String, // From cw2
+    pub admin: Option<Addr>,
+    pub features: Vec<String>, // Names of the optional behaviours enabled, like "creation_fee"
+}
+
+// Info of a DAO DAO module
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct InfoResponse {
+    pub info: ContractVersion,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
-pub enum MigrateMsg {}
+pub struct MigrateMsg {}
@@ -1,28 +1,656 @@
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
-use cosmwasm_std::Addr;
-use cw_storage_plus::{Item, Map};
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+use cosmwasm_std::{
+    Addr, Coin, CosmosMsg, Decimal, Empty, StdError, StdResult, Storage, SubMsg, Timestamp, Uint128,
+};
+use cw20::Cw20CoinVerified;
+use cw_storage_plus::{
+    Index, IndexList, IndexedMap, Item, KeyDeserialize, Map, MultiIndex, Prefix, SnapshotMap,
+    Strategy,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
-    pub admin: Addr,
+    // Minimum seconds between two vote actions of the same address
+    pub vote_cooldown: Option<u64>,
+    // Minimum and maximum number of options of a poll
+    pub min_options: u32,
+    pub max_options: u32,
+    // Whether anyone can create polls, otherwise only moderators and above
+    pub allow_public_creation: bool,
+    // Blocks poll creation and voting during incidents
+    pub paused: bool,
+    // Fee paid to the contract to create a poll
+    pub creation_fee: Option<Coin>,
+    // Same fee paid in a cw20 token, sending it with ReceiveMsg::CreatePoll
+    pub cw20_creation_fee: Option<Cw20CoinVerified>,
+    // Refundable deposit paid with the creation fee, slashed if the poll is removed
+    pub creation_deposit: Option<Coin>,
+    // Share of the creation deposit sent to the treasury when the poll is removed
+    pub deposit_slash_ratio: Decimal,
+    // Distinct flags that pause a poll until a moderator reviews it
+    pub flag_threshold: Option<u32>,
+    // Polls a creator can have open or paused at the same time
+    pub max_open_polls_per_creator: Option<u32>,
+    // Bytes the question and each option of a poll can take
+    #[serde(default = "default_max_question_length")]
+    pub max_question_length: u32,
+    #[serde(default = "default_max_option_length")]
+    pub max_option_length: u32,
+    // Extra diagnostic attributes on votes, meant for testnets
+    #[serde(default)]
+    pub debug_events: bool,
+    // cw721 contract minting an "I Voted" NFT on the first ballot of an address
+    // in a poll, this contract being its minter
+    #[serde(default)]
+    pub voted_nft: Option<Addr>,
+    // Tokenfactory denom administered by this contract, one unit is minted on
+    // the first ballot of an address in a poll. Needs the tokenfactory feature
+    #[serde(default)]
+    pub participation_denom: Option<String>,
+    // Nois proxy the contract requests verifiable randomness from
+    #[serde(default)]
+    pub nois_proxy: Option<Addr>,
+    // CronCat tasks contract running the FinalizeExpired of scheduled polls
+    #[serde(default)]
+    pub croncat_tasks: Option<Addr>,
+    // DAO DAO core the contract is a proposal module of, its voting power weighs
+    // the ballots of the polls created meanwhile
+    #[serde(default)]
+    pub dao: Option<Addr>,
+}
+
+pub const DEFAULT_MAX_QUESTION_LENGTH: u32 = 280;
+pub const DEFAULT_MAX_OPTION_LENGTH: u32 = 64;
+
+fn default_max_question_length() -> u32 {
+    DEFAULT_MAX_QUESTION_LENGTH
+}
+
+fn default_max_option_length() -> u32 {
+    DEFAULT_MAX_OPTION_LENGTH
+}
+
+// Admin role levels below the contract owner (cw-ownable), declared from the
+// lowest to the highest so they can be compared
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Moderator, // Moderates polls
+    Manager,   // Moderator rights, plus managing moderators and operational actions
+}
+
+impl Role {
+    // Name of the role in the attributes
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Moderator => "moderator",
+            Role::Manager => "manager",
+        }
+    }
+}
+
+pub const MAX_POLL_ID_LENGTH: usize = 64;
+
+// Id of a poll in messages, checked when deserialized so malformed ids are
+// rejected before reaching the contract. Storage keys are the inner string
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(try_from = "String", into = "String")]
+pub struct PollId(String);
+
+impl PollId {
+    // Letters, digits, '-', '_', '.' and '/', at most MAX_POLL_ID_LENGTH of them
+    pub fn new(poll_id: impl Into<String>) -> StdResult<Self> {
+        let poll_id = poll_id.into();
+        let valid_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/');
+        if poll_id.is_empty()
+            || poll_id.len() > MAX_POLL_ID_LENGTH
+            || !poll_id.chars().all(valid_char)
+        {
+            return Err(StdError::generic_err(format!("Invalid poll id: {poll_id}")));
+        }
+        Ok(PollId(poll_id))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for PollId {
+    type Error = StdError;
+
+    fn try_from(poll_id: String) -> StdResult<Self> {
+        PollId::new(poll_id)
+    }
+}
+
+impl FromStr for PollId {
+    type Err = StdError;
+
+    fn from_str(poll_id: &str) -> StdResult<Self> {
+        PollId::new(poll_id)
+    }
 }
 
+impl From<PollId> for String {
+    fn from(poll_id: PollId) -> Self {
+        poll_id.0
+    }
+}
+
+impl fmt::Display for PollId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PollStatus {
+    #[default]
+    Open, // Accepting votes
+    Closed,  // Finalized, tallies are the result
+    Vetoed,  // Finalized by the veto, the result doesn't count regardless of tallies
+    Removed, // Taken down by a moderator, see REMOVALS
+    Paused,  // Flagged by the community, waiting for a moderator
+}
+
+impl PollStatus {
+    // Key of the status index of POLLS
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PollStatus::Open => "open",
+            PollStatus::Closed => "closed",
+            PollStatus::Vetoed => "vetoed",
+            PollStatus::Removed => "removed",
+            PollStatus::Paused => "paused",
+        }
+    }
+}
+
+// Messages the contract sends when option wins the poll, turning it into a
+// proposal. Quorum is the minimum of votes counted, threshold the minimum
+// share of them the option needs
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PollActions {
+    pub option: String,
+    pub msgs: Vec<CosmosMsg>,
+    pub quorum: Option<Uint128>,
+    pub threshold: Option<Decimal>,
+}
+
+// Outcome of a poll closed with several options sharing the lead
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TieBreak {
+    #[default]
+    NoWinner,
+    // One of the leaders drawn with Nois randomness once closed
+    Random,
+}
+
+// Answer of a poll. Ids follow the order of the options at creation and never
+// change, votes are kept apart in TALLIES under the label
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PollOption {
+    pub id: u8,
+    pub label: String,
+}
+
+// Every layout the options of a poll were stored with
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum StoredOption {
+    Option(PollOption),
+    // Before PollOption
+    Label(String),
+    // Before TALLIES, the votes were kept with the label
+    Tally(String, u64),
+}
+
+// Only the options of a stored poll, to get at the votes of the first layout
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StoredOptions {
+    pub options: Vec<StoredOption>,
+}
+
+// Older options get their position as id until the migration rewrites them. Votes
+// kept in the options only count once the migration moved them to TALLIES
+fn deserialize_options<'de, D>(deserializer: D) -> Result<Vec<PollOption>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let options = Vec::<StoredOption>::deserialize(deserializer)?;
+    Ok(options
+        .into_iter()
+        .enumerate()
+        .map(|(id, option)| match option {
+            StoredOption::Option(option) => option,
+            StoredOption::Label(label) | StoredOption::Tally(label, _) => PollOption {
+                id: id as u8,
+                label,
+            },
+        })
+        .collect())
+}
+
+// Fields added after the first layout need a default, so polls stored before
+// them remain readable. Options are decoded from any of their layouts
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct Poll {
     pub creator: Addr,
     pub question: String,
-    pub options: Vec<(String, u64)>,
+    #[serde(deserialize_with = "deserialize_options")]
+    pub options: Vec<PollOption>,
+    // Polls couldn't be closed yet in the first layout
+    #[serde(default)]
+    pub status: PollStatus,
+    // Address allowed to veto the poll besides managers
+    pub veto: Option<Addr>,
+    // Coin voters attach to their first ballot, refunded once the poll is finalized
+    pub vote_deposit: Option<Coin>,
+    // Voting stops at this time, the poll still has to be closed
+    pub expires_at: Option<Timestamp>,
+    // Votes cast, a changed vote counts again. Missing in polls stored before
+    #[serde(default)]
+    pub total_votes: u64,
+    // Addresses with a ballot, not counting changed votes again
+    #[serde(default, alias = "unique_voters")]
+    pub voter_count: u64,
+    // Block of the creation
+    #[serde(default)]
+    pub created_at: Timestamp,
+    #[serde(default)]
+    pub created_height: u64,
+    // Sorted and without duplicates, see TagIndex
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // Tallies are only revealed with a permit until the poll is finalized
+    #[serde(default)]
+    pub hide_results: bool,
+    // Votes only write the ballots, TALLIES are counted from them when the poll is closed
+    #[serde(default)]
+    pub lazy_tally: bool,
+    // cw20 token voters send with ReceiveMsg::Vote, the amount being the weight
+    #[serde(default)]
+    pub vote_token: Option<Addr>,
+    // What a tie for the lead at closing means
+    #[serde(default)]
+    pub tie_break: TieBreak,
+    // Option a random tie break picked among the tied leaders
+    #[serde(default)]
+    pub tie_winner: Option<String>,
+    // DAO weighing the native ballots with the voting power at created_height
+    #[serde(default)]
+    pub dao: Option<Addr>,
+    // Alias of the poll in SLUGS
+    #[serde(default)]
+    pub slug: Option<String>,
+}
+
+impl Poll {
+    pub fn has_option(&self, label: &str) -> bool {
+        self.options.iter().any(|option| option.label == label)
+    }
+}
+
+// Why and by whom a poll was taken down
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Removal {
+    pub moderator: Addr,
+    pub reason: String,
+    pub removed_at: Timestamp,
+}
+
+// Intervention of an admin or moderator, see AUDIT_LOG
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct AuditEntry {
+    pub actor: Addr,
+    pub action: String, // Named like the action attributes
+    pub poll_id: Option<String>,
+    pub height: u64,
+    pub time: Timestamp,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct Ballot {
     pub option: String,
+    // Weight counted for this ballot, 1 for one-address-one-vote polls. Ballots
+    // stored before weights were one vote
+    #[serde(default = "Uint128::one")]
+    pub weight: Uint128,
+}
+
+// Rewards of a poll, shared among its voters by ballot weight once it is closed
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct RewardPool {
+    pub native: Vec<Coin>,
+    pub cw20: Vec<Cw20CoinVerified>,
+}
+
+impl RewardPool {
+    pub fn is_empty(&self) -> bool {
+        self.native.is_empty() && self.cw20.is_empty()
+    }
+
+    // Merges the amounts of the same denom or token
+    pub fn add(&mut self, other: RewardPool) {
+        for coin in other.native {
+            match self.native.iter_mut().find(|c| c.denom == coin.denom) {
+                Some(c) => c.amount += coin.amount,
+                None => self.native.push(coin),
+            }
+        }
+        for coin in other.cw20 {
+            match self.cw20.iter_mut().find(|c| c.address == coin.address) {
+                Some(c) => c.amount += coin.amount,
+                None => self.cw20.push(coin),
+            }
+        }
+    }
+
+    // Part of the pool owed to weight out of total, rounded down
+    pub fn share(&self, weight: Uint128, total: Uint128) -> RewardPool {
+        RewardPool {
+            native: self
+                .native
+                .iter()
+                .map(|c| Coin::new(c.amount.multiply_ratio(weight, total).u128(), &c.denom))
+                .filter(|c| !c.amount.is_zero())
+                .collect(),
+            cw20: self
+                .cw20
+                .iter()
+                .map(|c| Cw20CoinVerified {
+                    address: c.address.clone(),
+                    amount: c.amount.multiply_ratio(weight, total),
+                })
+                .filter(|c| !c.amount.is_zero())
+                .collect(),
+        }
+    }
+}
+
+// Prize drawn among the voters of a finalized poll with Nois randomness
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Lottery {
+    // Started the lottery, gets back the prizes no voter is left for
+    pub sponsor: Addr,
+    pub winners: u32,
+    // Paid to each winner
+    pub prize: Coin,
+    // None until the randomness arrives
+    pub drawn: Option<Vec<Addr>>,
+}
+
+// What a randomness request to the Nois proxy was made for
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RandomnessJob {
+    Lottery { poll_id: String },
+    TieBreak { poll_id: String },
+}
+
+// Contract wide counters, never decremented
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct Stats {
+    pub total_polls: u64,
+    // Ballots cast, one per address and poll, changed votes don't count again.
+    // Stored as total_votes before
+    #[serde(alias = "total_votes")]
+    pub total_ballots: u64,
+    pub unique_voters: u64,
+}
+
+// Config of the first layout, only the admin, read by the migration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FirstConfig {
+    pub admin: Addr,
+}
+
+// Only the option of a stored ballot, ballots of the first layout had no weight
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StoredBallot {
+    pub option: String,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
+// Same entry as CONFIG, read by the migration
+pub const FIRST_CONFIG: Item<FirstConfig> = Item::new("config");
+pub const STATS: Item<Stats> = Item::new("stats");
+// Polls currently stored, unlike Stats::total_polls removed polls are subtracted
+pub const POLL_COUNT: Item<u64> = Item::new("poll_count");
+// Number assigned as id to the next poll
+pub const NEXT_POLL_ID: Item<u64> = Item::new("next_poll_id");
+// Poll id of each slug, so links can use a readable name
+pub const SLUGS: Map<String, String> = Map::new("slugs");
+pub const ROLES: Map<Addr, Role> = Map::new("roles");
+// Creation fees collected so far, by denom
+pub const FEES: Map<String, Uint128> = Map::new("fees");
+// Creation fees collected so far in cw20 tokens, by token contract
+pub const CW20_FEES: Map<Addr, Uint128> = Map::new("cw20_fees");
+// Creation deposits held until reclaimed by the creator, by poll_id
+pub const CREATION_DEPOSITS: Map<String, Coin> = Map::new("creation_deposits");
+// Addresses that can neither create polls nor vote
+pub const BANNED: Map<Addr, Empty> = Map::new("banned");
 
 // Our consts
-pub const POLLS: Map<String, Poll> = Map::new("polls");
-pub const BALLOTS: Map<(Addr, String), Ballot> = Map::new("ballots");
+pub const POLLS: IndexedMap<&str, Poll, PollIndexes> = IndexedMap::new(
+    "polls",
+    PollIndexes {
+        creator: MultiIndex::new(|_pk, poll| poll.creator.clone(), "polls", "polls__creator"),
+        status: MultiIndex::new(
+            |_pk, poll| poll.status.as_str().to_string(),
+            "polls",
+            "polls__status",
+        ),
+        creator_status: MultiIndex::new(
+            |_pk, poll| (poll.creator.clone(), poll.status.as_str().to_string()),
+            "polls",
+            "polls__creator_status",
+        ),
+        expiration: MultiIndex::new(expiration_key, "polls", "polls__expiration"),
+        created: MultiIndex::new(
+            |_pk, poll| poll.created_at.nanos(),
+            "polls",
+            "polls__created",
+        ),
+        tags: TagIndex::new("polls__tags"),
+    },
+);
+// Same entries as POLLS, read by the migration
+pub const STORED_OPTIONS: Map<&str, StoredOptions> = Map::new("polls");
+// Same entries as POLLS without touching the indexes, only for updates that
+// leave every indexed field as it was
+pub const POLL_ENTRIES: Map<&str, Poll> = Map::new("polls");
+// Checkpointed at the block after each poll close, see QueryMsg::VoteAtHeight
+pub const BALLOTS: SnapshotMap<(&Addr, &str), Ballot> = SnapshotMap::new(
+    "ballots",
+    "ballots__checkpoints",
+    "ballots__changelog",
+    Strategy::Selected,
+);
+// Same entries as BALLOTS, read by the migration
+pub const STORED_BALLOTS: Map<(&Addr, &str), StoredBallot> = Map::new("ballots");
+// Addresses with a ballot in each poll, by (poll_id, voter), to find the ballots of a poll
+pub const VOTERS: Map<(String, Addr), Empty> = Map::new("voters");
+// Same by (poll_id, option, voter), to find who voted each option
+pub const OPTION_VOTERS: Map<(String, String, Addr), Empty> = Map::new("option_voters");
+// Polls each address voted in, and the same by (count, voter) to rank them
+pub const PARTICIPATION: Map<Addr, u64> = Map::new("participation");
+pub const PARTICIPATION_RANKING: Map<(u64, Addr), Empty> = Map::new("participation_ranking");
+// Votes of each (poll_id, option), with their history to query past heights.
+// Out of Poll so changing a vote doesn't rewrite the whole poll
+pub const TALLIES: SnapshotMap<(String, String), Uint128> = SnapshotMap::new(
+    "tallies",
+    "tallies__checkpoints",
+    "tallies__changelog",
+    Strategy::EveryBlock,
+);
+// Number of open or paused polls of each creator
+pub const OPEN_POLLS: Map<Addr, u32> = Map::new("open_polls");
+// Community flags and their reason, by (poll_id, flagger)
+pub const FLAGS: Map<(String, Addr), String> = Map::new("flags");
+// Number of flags of each poll since its last review
+pub const FLAG_COUNTS: Map<String, u32> = Map::new("flag_counts");
+// Moderation record of each removed poll, by poll_id
+pub const REMOVALS: Map<String, Removal> = Map::new("removals");
+// Admin and moderator actions in the order they happened, by sequence number
+pub const AUDIT_LOG: Map<u64, AuditEntry> = Map::new("audit_log");
+// Next expected nonce of each signed vote voter, protects against replays
+pub const VOTE_NONCES: Map<Addr, u64> = Map::new("vote_nonces");
+// Vote deposits held by the contract until claimed back, by (voter, poll_id)
+pub const DEPOSITS: Map<(Addr, String), Coin> = Map::new("deposits");
+// Tokens sent as vote weight, escrowed until claimed back, by (voter, poll_id)
+pub const VOTE_ESCROW: Map<(Addr, String), Cw20CoinVerified> = Map::new("vote_escrow");
+// Rewards funded for the voters of each poll, by poll_id. Totals, claims don't
+// decrease them
+pub const REWARD_POOLS: Map<String, RewardPool> = Map::new("reward_pools");
+// What each sponsor put in a reward pool, refunded if the poll ends without
+// being closed with votes, by (poll_id, sponsor)
+pub const REWARD_SPONSORS: Map<(String, Addr), RewardPool> = Map::new("reward_sponsors");
+// Voters who claimed their share of a reward pool, by (poll_id, voter)
+pub const REWARD_CLAIMS: Map<(String, Addr), Empty> = Map::new("reward_claims");
+// Lottery of each poll, by poll_id
+pub const LOTTERIES: Map<String, Lottery> = Map::new("lotteries");
+// Randomness requests waiting for their Nois callback, by job_id
+pub const RANDOMNESS_JOBS: Map<String, RandomnessJob> = Map::new("randomness_jobs");
+// Number of the next randomness request, job ids are never reused
+pub const NEXT_JOB_ID: Item<u64> = Item::new("next_job_id");
+// Hash of the CronCat task finalizing each scheduled poll, by poll_id
+pub const FINALIZE_TASKS: Map<String, String> = Map::new("finalize_tasks");
+// Poll whose task is being created, until the reply brings its hash
+pub const PENDING_FINALIZE_TASK: Item<String> = Item::new("pending_finalize_task");
+// Contracts sent a PollHookMsg on poll creation, votes and close
+pub const HOOKS: Hooks = Hooks::new("hooks");
+// Hooks of a DAO DAO proposal module, registered by the DAO
+pub const PROPOSAL_HOOKS: Hooks = Hooks::new("proposal_hooks");
+pub const VOTE_HOOKS: Hooks = Hooks::new("vote_hooks");
+// Actions of the polls created as proposals, by poll_id
+pub const POLL_ACTIONS: Map<String, PollActions> = Map::new("poll_actions");
+// Time of the last vote action of each address, only tracked with a vote cooldown
+pub const LAST_ACTION: Map<Addr, Timestamp> = Map::new("last_action");
+// Contract each address authorized to cast its votes, by voter
+pub const VOTE_PROXIES: Map<Addr, Addr> = Map::new("vote_proxies");
+
+// Secondary indexes of POLLS, to list polls without scanning all of them
+pub struct PollIndexes<'a> {
+    pub creator: MultiIndex<'a, Addr, Poll, String>,
+    pub status: MultiIndex<'a, String, Poll, String>,
+    pub creator_status: MultiIndex<'a, (Addr, String), Poll, String>,
+    pub expiration: MultiIndex<'a, u64, Poll, String>,
+    pub created: MultiIndex<'a, u64, Poll, String>,
+    pub tags: TagIndex<'a>,
+}
+
+impl<'a> IndexList<Poll> for PollIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Poll>> + '_> {
+        let v: Vec<&dyn Index<Poll>> = vec![
+            &self.creator,
+            &self.status,
+            &self.creator_status,
+            &self.expiration,
+            &self.created,
+            &self.tags,
+        ];
+        Box::new(v.into_iter())
+    }
+}
+
+// Contracts notified of the polls, the Hooks of cw-controllers kept to what
+// this contract needs
+pub struct Hooks<'a>(Item<'a, Vec<Addr>>);
+
+impl<'a> Hooks<'a> {
+    pub const fn new(namespace: &'a str) -> Self {
+        Hooks(Item::new(namespace))
+    }
+
+    // False if addr was already registered
+    pub fn add_hook(&self, storage: &mut dyn Storage, addr: Addr) -> StdResult<bool> {
+        let mut hooks = self.0.may_load(storage)?.unwrap_or_default();
+        if hooks.contains(&addr) {
+            return Ok(false);
+        }
+        hooks.push(addr);
+        self.0.save(storage, &hooks)?;
+        Ok(true)
+    }
+
+    // False if addr wasn't registered
+    pub fn remove_hook(&self, storage: &mut dyn Storage, addr: &Addr) -> StdResult<bool> {
+        let mut hooks = self.0.may_load(storage)?.unwrap_or_default();
+        let Some(index) = hooks.iter().position(|hook| hook == addr) else {
+            return Ok(false);
+        };
+        hooks.remove(index);
+        self.0.save(storage, &hooks)?;
+        Ok(true)
+    }
+
+    pub fn hooks(&self, storage: &dyn Storage) -> StdResult<Vec<Addr>> {
+        Ok(self.0.may_load(storage)?.unwrap_or_default())
+    }
+
+    // One message for each hook
+    pub fn prepare_hooks<F: Fn(Addr) -> StdResult<SubMsg>>(
+        &self,
+        storage: &dyn Storage,
+        prep: F,
+    ) -> StdResult<Vec<SubMsg>> {
+        self.hooks(storage)?.into_iter().map(prep).collect()
+    }
+}
+
+// Polls of each tag, by (tag, poll_id). A MultiIndex takes a single key per
+// poll, this one has an entry for each of its tags
+pub struct TagIndex<'a> {
+    polls: Map<'a, (String, String), Empty>,
+}
+
+impl<'a> TagIndex<'a> {
+    pub const fn new(namespace: &'a str) -> Self {
+        TagIndex {
+            polls: Map::new(namespace),
+        }
+    }
+
+    // Poll ids with the tag
+    pub fn prefix(&self, tag: String) -> Prefix<String, Empty, String> {
+        self.polls.prefix(tag)
+    }
+}
+
+impl<'a> Index<Poll> for TagIndex<'a> {
+    fn save(&self, store: &mut dyn Storage, pk: &[u8], data: &Poll) -> StdResult<()> {
+        let poll_id = String::from_vec(pk.to_vec())?;
+        for tag in &data.tags {
+            self.polls
+                .save(store, (tag.clone(), poll_id.clone()), &Empty {})?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, store: &mut dyn Storage, pk: &[u8], old_data: &Poll) -> StdResult<()> {
+        let poll_id = String::from_vec(pk.to_vec())?;
+        for tag in &old_data.tags {
+            self.polls.remove(store, (tag.clone(), poll_id.clone()));
+        }
+        Ok(())
+    }
+}
+
+// Open polls by expiration in nanoseconds, the others are all sorted last
+fn expiration_key(_pk: &[u8], poll: &Poll) -> u64 {
+    match (&poll.status, poll.expires_at) {
+        (PollStatus::Open, Some(expires_at)) => expires_at.nanos(),
+        _ => u64::MAX,
+    }
+}
@@ -0,0 +1,32 @@
+// Messages of the tokenfactory module, encoded by hand since they only take a
+// few string fields
+use cosmwasm_std::{Addr, CosmosMsg};
+
+const MSG_MINT: &str = "/osmosis.tokenfactory.v1beta1.MsgMint";
+
+// Length delimited protobuf field
+fn encode_field(buf: &mut Vec<u8>, field: u8, value: &[u8]) {
+    buf.push(field << 3 | 2);
+    let mut len = value.len();
+    while len >= 0x80 {
+        buf.push(len as u8 | 0x80);
+        len >>= 7;
+    }
+    buf.push(len as u8);
+    buf.extend_from_slice(value);
+}
+
+// Mints amount of denom to recipient, contract being the admin of the denom
+pub fn mint_msg(contract: &Addr, denom: &str, amount: u128, recipient: &Addr) -> CosmosMsg {
+    let mut coin = vec![];
+    encode_field(&mut coin, 1, denom.as_bytes());
+    encode_field(&mut coin, 2, amount.to_string().as_bytes());
+    let mut value = vec![];
+    encode_field(&mut value, 1, contract.as_bytes());
+    encode_field(&mut value, 2, &coin);
+    encode_field(&mut value, 3, recipient.as_bytes());
+    CosmosMsg::Stargate {
+        type_url: MSG_MINT.to_string(),
+        value: value.into(),
+    }
+}